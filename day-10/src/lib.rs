@@ -1,3 +1,4 @@
 mod map;
 pub mod part_1;
 pub mod part_2;
+pub mod render;
@@ -1,9 +1,14 @@
 use crate::map::{pipe::PipeType, Map, PieceType};
 
 pub fn process(input: &str) -> u32 {
+    inside_tiles(input).len() as u32
+}
+
+/// Tiles enclosed by the main loop as (row, column)
+pub fn inside_tiles(input: &str) -> Vec<(usize, usize)> {
     let map = Map::init(input);
 
-    let mut count_inside = 0;
+    let mut inside = Vec::new();
     for i in 1..map.map.len() - 1 {
         let line = &map.map[i];
         for j in 1..line.len() - 1 {
@@ -55,11 +60,11 @@ pub fn process(input: &str) -> u32 {
                 }
             }
             if count != 0 {
-                count_inside += 1;
+                inside.push((i, j));
             }
         }
     }
-    return count_inside;
+    inside
 }
 
 #[cfg(test)]
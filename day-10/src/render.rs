@@ -0,0 +1,40 @@
+use crate::{map::Map, part_2};
+
+/// Steps to the farthest tile of the main loop, and the loop in the order it's walked
+pub fn main_loop(input: &str) -> (u32, Vec<(usize, usize)>) {
+    let map = Map::init(input);
+    let tiles: Vec<(usize, usize)> = map
+        .polygon_points
+        .iter()
+        .map(|point| (point.i, point.j))
+        .collect();
+    ((tiles.len() / 2) as u32, tiles)
+}
+
+/// The tiles the main loop encloses
+pub fn inside_tiles(input: &str) -> (u32, Vec<(usize, usize)>) {
+    let tiles = part_2::inside_tiles(input);
+    (tiles.len() as u32, tiles)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_main_loop() {
+        let input = include_str!("./inputs/test.txt");
+        let (farthest, main_loop) = main_loop(input);
+        assert_eq!(farthest, 4);
+        assert_eq!(main_loop.len(), 8);
+        assert_eq!(main_loop[0], (1, 1));
+    }
+
+    #[test]
+    fn test_inside_tiles() {
+        let input = include_str!("./inputs/test3.txt");
+        let (count, inside) = inside_tiles(input);
+        assert_eq!(count, 4);
+        assert_eq!(inside, vec![(6, 2), (6, 3), (6, 7), (6, 8)]);
+    }
+}
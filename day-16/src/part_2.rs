@@ -1,5 +1,6 @@
 use crate::grid::{tile::Movement, Grid};
 
+#[cfg(not(target_arch = "wasm32"))]
 pub fn process(input: &str) -> usize {
     let grid = Grid::new(input);
     let mut handles = Vec::new();
@@ -23,6 +24,21 @@ pub fn process(input: &str) -> usize {
     count
 }
 
+// wasm has no threads, the worker counts the sides one after the other
+#[cfg(target_arch = "wasm32")]
+pub fn process(input: &str) -> usize {
+    let grid = Grid::new(input);
+    [
+        count_top_corner(grid.clone()),
+        count_bottom_corner(grid.clone()),
+        count_left_corner(grid.clone()),
+        count_right_corner(grid),
+    ]
+    .into_iter()
+    .max()
+    .unwrap()
+}
+
 fn count_top_corner(mut grid: Grid) -> usize {
     let mut count = 0;
     let len: isize = grid.tiles[0].len() as isize;
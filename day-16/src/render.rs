@@ -0,0 +1,71 @@
+use crate::grid::{tile::Movement, Grid};
+
+/// The tiles energized by the beam entering the top-left corner heading right
+pub fn energized_tiles(input: &str) -> (usize, Vec<(usize, usize)>) {
+    let mut grid = Grid::new(input);
+    grid.energize();
+    let energized = collect_energized(&grid);
+    (energized.len(), energized)
+}
+
+/// The tiles energized by the best beam entering from an edge, trying them one by one
+pub fn best_energized_tiles(input: &str) -> (usize, Vec<(usize, usize)>) {
+    let mut grid = Grid::new(input);
+    let rows = grid.tiles.len() as isize;
+    let columns = grid.tiles[0].len() as isize;
+
+    let mut starts = Vec::new();
+    for j in 0..columns {
+        starts.push(((0, j), Movement::Down));
+        starts.push(((rows - 1, j), Movement::Up));
+    }
+    for i in 0..rows {
+        starts.push(((i, 0), Movement::Right));
+        starts.push(((i, columns - 1), Movement::Left));
+    }
+
+    let mut best = Vec::new();
+    for (location, direction) in starts {
+        grid.energize_from(location, direction);
+        let energized = collect_energized(&grid);
+        if energized.len() > best.len() {
+            best = energized;
+        }
+        grid.reset();
+    }
+    (best.len(), best)
+}
+
+fn collect_energized(grid: &Grid) -> Vec<(usize, usize)> {
+    let mut energized = Vec::new();
+    for (i, row) in grid.tiles.iter().enumerate() {
+        for (j, tile) in row.iter().enumerate() {
+            if tile.energized {
+                energized.push((i, j));
+            }
+        }
+    }
+    energized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_energized_tiles() {
+        let input = include_str!("./inputs/test.txt");
+        let (count, energized) = energized_tiles(input);
+        assert_eq!(count, 46);
+        assert_eq!(energized.len(), 46);
+        assert_eq!(energized[0], (0, 0));
+    }
+
+    #[test]
+    fn test_best_energized_tiles() {
+        let input = include_str!("./inputs/test.txt");
+        let (count, energized) = best_energized_tiles(input);
+        assert_eq!(count, 51);
+        assert_eq!(energized.len(), 51);
+    }
+}
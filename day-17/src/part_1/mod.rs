@@ -29,6 +29,12 @@ struct State {
 }
 
 pub fn process(input: &str) -> usize {
+    let (cost, _) = crucible_path(input);
+    cost
+}
+
+/// Minimum heat loss and the path that causes it, as (row, column) from the source to the destination
+pub(crate) fn crucible_path(input: &str) -> (usize, Vec<(usize, usize)>) {
     let mut matrix = Vec::new();
     for line in input.lines() {
         let mut row = Vec::new();
@@ -44,14 +50,24 @@ pub fn process(input: &str) -> usize {
         j: matrix[0].len() - 1,
     };
 
-    let cost = get_cost(&matrix, source, destination);
+    let (cost, path) = get_cost(&matrix, source, destination);
+    let path = path
+        .iter()
+        .map(|position| (position.i, position.j))
+        .collect();
 
-    cost
+    (cost, path)
 }
 
-fn get_cost(map: &Vec<Vec<usize>>, source: Position, destination: Position) -> usize {
+fn get_cost(
+    map: &Vec<Vec<usize>>,
+    source: Position,
+    destination: Position,
+) -> (usize, Vec<Position>) {
     // Hashmap representing the minimum cost to reach a given state
     let mut costs: HashMap<State, usize> = HashMap::new();
+    // Hashmap representing the state used to reach a given state with its minimum cost
+    let mut previous: HashMap<State, State> = HashMap::new();
     // Min-heap that will be used to find the state with the lowest cost
     let mut heap: BinaryHeap<Reverse<(usize, State)>> = BinaryHeap::new();
 
@@ -68,7 +84,7 @@ fn get_cost(map: &Vec<Vec<usize>>, source: Position, destination: Position) -> u
 
     while let Some(Reverse((curr_cost, curr_state))) = heap.pop() {
         if curr_state.position == destination {
-            return curr_cost;
+            return (curr_cost, walk_back(&previous, curr_state));
         }
 
         let cost_to_curr_state = *costs.get(&curr_state).unwrap_or(&usize::MAX);
@@ -86,10 +102,23 @@ fn get_cost(map: &Vec<Vec<usize>>, source: Position, destination: Position) -> u
             if next_cost < *costs.get(&neighbor).unwrap_or(&usize::MAX) {
                 heap.push(Reverse((next_cost, neighbor)));
                 costs.insert(neighbor, next_cost);
+                previous.insert(neighbor, curr_state);
             }
         }
     }
-    usize::MAX
+    (usize::MAX, Vec::new())
+}
+
+//Walk back from the destination state to the source, following the states used to reach each one
+fn walk_back(previous: &HashMap<State, State>, destination: State) -> Vec<Position> {
+    let mut path = vec![destination.position];
+    let mut state = destination;
+    while let Some(previous_state) = previous.get(&state) {
+        path.push(previous_state.position);
+        state = *previous_state;
+    }
+    path.reverse();
+    path
 }
 
 //Get the neighbors of a given state
@@ -33,6 +33,12 @@ struct State {
 }
 
 pub fn process(input: &str) -> usize {
+    let (cost, _) = crucible_path(input);
+    cost
+}
+
+/// Minimum heat loss and the path that causes it, as (row, column) from the source to the destination
+pub(crate) fn crucible_path(input: &str) -> (usize, Vec<(usize, usize)>) {
     let mut matrix = Vec::new();
     for line in input.lines() {
         let mut row = Vec::new();
@@ -48,14 +54,24 @@ pub fn process(input: &str) -> usize {
         j: matrix[0].len() - 1,
     };
 
-    let cost = get_cost(&matrix, source, destination);
+    let (cost, path) = get_cost(&matrix, source, destination);
+    let path = path
+        .iter()
+        .map(|position| (position.i, position.j))
+        .collect();
 
-    cost
+    (cost, path)
 }
 
-fn get_cost(map: &Vec<Vec<usize>>, source: Position, destination: Position) -> usize {
+fn get_cost(
+    map: &Vec<Vec<usize>>,
+    source: Position,
+    destination: Position,
+) -> (usize, Vec<Position>) {
     // Hashmap representing the minimum cost to reach a given state
     let mut costs: HashMap<State, usize> = HashMap::new();
+    // Hashmap representing the state used to reach a given state with its minimum cost
+    let mut previous: HashMap<State, State> = HashMap::new();
     // Min-heap that will be used to find the state with the lowest cost
     let mut heap: BinaryHeap<Reverse<(usize, State)>> = BinaryHeap::new();
 
@@ -75,11 +91,11 @@ fn get_cost(map: &Vec<Vec<usize>>, source: Position, destination: Position) -> u
 
         if current_position == destination {
             match curr_state.direction {
-                Direction::Start => return curr_cost,
+                Direction::Start => return (curr_cost, walk_back(&previous, curr_state)),
                 Direction::Right => {
                     let used_moves = MAX_MOVES - curr_state.right_available;
                     if used_moves >= MIN_MOVES {
-                        return curr_cost;
+                        return (curr_cost, walk_back(&previous, curr_state));
                     } else {
                         continue;
                     }
@@ -87,7 +103,7 @@ fn get_cost(map: &Vec<Vec<usize>>, source: Position, destination: Position) -> u
                 Direction::Left => {
                     let used_moves = MAX_MOVES - curr_state.left_available;
                     if used_moves >= MIN_MOVES {
-                        return curr_cost;
+                        return (curr_cost, walk_back(&previous, curr_state));
                     } else {
                         continue;
                     }
@@ -95,7 +111,7 @@ fn get_cost(map: &Vec<Vec<usize>>, source: Position, destination: Position) -> u
                 Direction::Up => {
                     let used_moves = MAX_MOVES - curr_state.up_available;
                     if used_moves >= MIN_MOVES {
-                        return curr_cost;
+                        return (curr_cost, walk_back(&previous, curr_state));
                     } else {
                         continue;
                     }
@@ -103,7 +119,7 @@ fn get_cost(map: &Vec<Vec<usize>>, source: Position, destination: Position) -> u
                 Direction::Down => {
                     let used_moves = MAX_MOVES - curr_state.down_available;
                     if used_moves >= MIN_MOVES {
-                        return curr_cost;
+                        return (curr_cost, walk_back(&previous, curr_state));
                     } else {
                         continue;
                     }
@@ -126,10 +142,23 @@ fn get_cost(map: &Vec<Vec<usize>>, source: Position, destination: Position) -> u
             if next_cost < *costs.get(&neighbor).unwrap_or(&usize::MAX) {
                 heap.push(Reverse((next_cost, neighbor)));
                 costs.insert(neighbor, next_cost);
+                previous.insert(neighbor, curr_state);
             }
         }
     }
-    usize::MAX
+    (usize::MAX, Vec::new())
+}
+
+//Walk back from the destination state to the source, following the states used to reach each one
+fn walk_back(previous: &HashMap<State, State>, destination: State) -> Vec<Position> {
+    let mut path = vec![destination.position];
+    let mut state = destination;
+    while let Some(previous_state) = previous.get(&state) {
+        path.push(previous_state.position);
+        state = *previous_state;
+    }
+    path.reverse();
+    path
 }
 
 //Get the neighbors of a given state
@@ -0,0 +1,47 @@
+use crate::{part_1, part_2};
+
+/// Least heat loss for the crucible, and its path from the top-left corner
+pub fn crucible_path(input: &str) -> (usize, Vec<(usize, usize)>) {
+    part_1::crucible_path(input)
+}
+
+/// Least heat loss for the ultra crucible, and its path from the top-left corner
+pub fn ultra_crucible_path(input: &str) -> (usize, Vec<(usize, usize)>) {
+    part_2::crucible_path(input)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn heat_loss(input: &str, path: &[(usize, usize)]) -> usize {
+        let matrix: Vec<Vec<usize>> = input
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|c| c.to_digit(10).unwrap() as usize)
+                    .collect()
+            })
+            .collect();
+        path.iter().skip(1).map(|(i, j)| matrix[*i][*j]).sum()
+    }
+
+    #[test]
+    fn test_crucible_path() {
+        let input = include_str!("./inputs/test.txt");
+        let (heat_loss_total, path) = crucible_path(input);
+        assert_eq!(heat_loss_total, 102);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(12, 12)));
+        assert_eq!(heat_loss(input, &path), 102);
+    }
+
+    #[test]
+    fn test_ultra_crucible_path() {
+        let input = include_str!("./inputs/test2.txt");
+        let (heat_loss_total, path) = ultra_crucible_path(input);
+        assert_eq!(heat_loss_total, 71);
+        assert_eq!(path.last(), Some(&(4, 11)));
+        assert_eq!(heat_loss(input, &path), 71);
+    }
+}
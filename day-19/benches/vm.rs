@@ -0,0 +1,53 @@
+// cargo bench -p day-19
+// Part 1 on the VM against the tree-walking interpreter, the input is compiled once for both
+
+use std::time::Instant;
+
+use day_19_lib::{
+    compiler::{self, types::Result},
+    generator::{self, Random},
+    interpreter,
+    vm::Bytecode,
+};
+
+fn main() {
+    let mut random = Random::new(0x2023);
+    let workflows = [
+        (
+            "example",
+            include_str!("../src/inputs/test.txt")
+                .split("\n\n")
+                .next()
+                .unwrap()
+                .to_string(),
+        ),
+        ("500 workflows", generator::workflows(&mut random, 500)),
+    ];
+    let parts = generator::parts(&mut random, 2_000_000);
+    for (name, workflows) in workflows {
+        let input = format!("{}\n\n{}", workflows, parts);
+
+        let compiled = compiler::compile(&input).unwrap();
+        let bytecode = Bytecode::compile(&input).unwrap();
+
+        let start = Instant::now();
+        let vm = compiled.pieces.iter().filter(|p| bytecode.run(p)).count();
+        let vm_time = start.elapsed();
+
+        let start = Instant::now();
+        let tree = interpreter::evaluate(compiled)
+            .into_iter()
+            .filter(|result| *result == Result::Accept)
+            .count();
+        let tree_time = start.elapsed();
+
+        assert_eq!(vm, tree);
+        println!(
+            "{}: vm {:?}, tree-walking interpreter {:?} ({:.1}x)",
+            name,
+            vm_time,
+            tree_time,
+            tree_time.as_secs_f64() / vm_time.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use std::io::{self, BufRead, Write};
+
+use day_19_lib::repl::{Session, HELP};
+
+// cargo run -p day-19 --bin repl -- path/to/workflows.txt
+fn main() {
+    let path = match std::env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: repl <file>");
+            std::process::exit(1);
+        }
+    };
+    let input = std::fs::read_to_string(&path).unwrap_or_else(|error| {
+        eprintln!("can't read {}: {}", path, error);
+        std::process::exit(1);
+    });
+    let mut session = Session::load(&input).unwrap_or_else(|errors| {
+        eprintln!("{}", errors);
+        std::process::exit(1);
+    });
+
+    println!("{}", HELP);
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+        if matches!(line.trim(), "quit" | "exit") {
+            break;
+        }
+        match session.execute(&line) {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => println!("{}", output),
+            Err(error) => eprintln!("{}", error),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt::Write;
+
+/// Byte range of the source, end excluded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub fn to(&self, other: Span) -> Span {
+        Span::new(self.start, other.end)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn error(message: String, span: Span) -> Self {
+        Self {
+            severity: Severity::Error,
+            message,
+            span,
+        }
+    }
+
+    pub fn warning(message: String, span: Span) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message,
+            span,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// The message followed by the source line it points to
+    ///
+    /// ```text
+    /// error: undefined workflow `qkx`
+    ///  --> 1:11
+    ///   |
+    /// 1 | px{a<2006:qkx,m>2090:A,rfg}
+    ///   |           ^^^
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let start = self.span.start.min(source.len());
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |i| start + i);
+        let line = &source[line_start..line_end];
+        let line_number = source[..start].matches('\n').count() + 1;
+        let column = source[line_start..start].chars().count();
+        let width = source[start..self.span.end.clamp(start, line_end)]
+            .chars()
+            .count()
+            .max(1);
+
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let gutter = " ".repeat(line_number.to_string().len());
+        let mut rendered = String::new();
+        let _ = writeln!(rendered, "{}: {}", severity, self.message);
+        let _ = writeln!(rendered, "{}--> {}:{}", gutter, line_number, column + 1);
+        let _ = writeln!(rendered, "{} |", gutter);
+        let _ = writeln!(rendered, "{} | {}", line_number, line);
+        let _ = write!(
+            rendered,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(column),
+            "^".repeat(width)
+        );
+        rendered
+    }
+}
+
+/// Every diagnostic rendered, separated by blank lines
+pub fn render_all(diagnostics: &[Diagnostic], source: &str) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.render(source))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let source = "in{s<1351:px,qqz}\npx{a<2006:qkx,m>2090:A,rfg}";
+        let diagnostic =
+            Diagnostic::error("undefined workflow `qkx`".to_string(), Span::new(28, 31));
+        assert_eq!(
+            diagnostic.render(source),
+            "error: undefined workflow `qkx`
+ --> 2:11
+  |
+2 | px{a<2006:qkx,m>2090:A,rfg}
+  |           ^^^"
+        );
+    }
+}
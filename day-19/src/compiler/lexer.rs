@@ -1,161 +1,182 @@
-use std::collections::HashMap;
+use super::diagnostic::Span;
 
-use crate::{FunctionMap, Piece};
-
-use super::types::{BinaryComparison, Comparator, Function, Result, Return, Statement, Variable};
-
-pub fn lex_pieces(piece: &str) -> Vec<Piece> {
-    let mut pieces = Vec::new();
-    for line in piece.lines() {
-        let mut piece_definition = line.trim_start_matches('{').trim_end_matches('}');
-        //x=787,m=2655,a=1222,s=2876
-        let mut piece = [0; 4];
-        loop {
-            let mid = match piece_definition.find(',') {
-                Some(i) => i,
-                None => {
-                    lex_variable(piece_definition, &mut piece);
-                    break;
-                }
-            };
-            let (variable, rest2) = piece_definition.split_at(mid);
-            let rest2 = rest2.trim_start_matches(',');
-            let variable = variable.trim();
-            lex_variable(variable, &mut piece);
-            piece_definition = rest2;
-            if piece_definition.len() == 0 {
-                break;
-            }
-        }
-        pieces.push(piece);
-    }
-    pieces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    Identifier(String),
+    Number(String),
+    LeftBrace,
+    RightBrace,
+    Comma,
+    Colon,
+    LessThan,
+    GreaterThan,
+    Equal,
+    NotEqual,
+    LessOrEqual,
+    GreaterOrEqual,
+    Newline,
+    Unknown(char),
 }
 
-pub fn lex_functions(input: &str) -> FunctionMap {
-    let mut map: FunctionMap = HashMap::new();
-    for line in input.lines() {
-        let line = line.trim();
-        let (function_name, rest) = line.split_at(line.find('{').unwrap());
-        let function_name = function_name.trim();
-        let function_definition = rest.trim_start_matches('{').trim_end_matches('}');
-        let function = lex_function(function_definition);
-        map.insert(function_name.to_string(), function);
-    }
-    map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
 }
 
-fn lex_variable(variable: &str, piece: &mut Piece) {
-    let mut chars = variable.chars();
-    let variable = chars.next().unwrap();
-    chars.next();
-    let value = chars.as_str().parse::<u16>().unwrap();
-    let variable = Variable::from_char(variable);
-    piece[variable as usize] = value;
+impl TokenKind {
+    /// How the token is written, for the diagnostics
+    pub fn describe(&self) -> String {
+        match self {
+            TokenKind::Identifier(identifier) => format!("`{}`", identifier),
+            TokenKind::Number(number) => format!("`{}`", number),
+            TokenKind::LeftBrace => "`{`".to_string(),
+            TokenKind::RightBrace => "`}`".to_string(),
+            TokenKind::Comma => "`,`".to_string(),
+            TokenKind::Colon => "`:`".to_string(),
+            TokenKind::LessThan => "`<`".to_string(),
+            TokenKind::GreaterThan => "`>`".to_string(),
+            TokenKind::Equal => "`=`".to_string(),
+            TokenKind::NotEqual => "`!=`".to_string(),
+            TokenKind::LessOrEqual => "`<=`".to_string(),
+            TokenKind::GreaterOrEqual => "`>=`".to_string(),
+            TokenKind::Newline => "end of line".to_string(),
+            TokenKind::Unknown(c) => format!("`{}`", c),
+        }
+    }
 }
 
-fn lex_function(function_definition: &str) -> Function {
-    let mut function: Function = Vec::new();
-    let (statement, rest) = function_definition.split_at(function_definition.find(',').unwrap());
-    let mut rest = rest.trim_start_matches(',');
-    let statement = statement.trim();
-    let statement = lex_statement(statement);
-    function.push(statement);
-    loop {
-        let mid = match rest.find(',') {
-            Some(i) => i,
-            None => {
-                let statement = lex_statement(rest);
-                function.push(statement);
-                break;
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let kind = match c {
+            '\n' => TokenKind::Newline,
+            c if c.is_whitespace() => continue,
+            '{' => TokenKind::LeftBrace,
+            '}' => TokenKind::RightBrace,
+            ',' => TokenKind::Comma,
+            ':' => TokenKind::Colon,
+            '<' if chars.next_if(|(_, c)| *c == '=').is_some() => TokenKind::LessOrEqual,
+            '>' if chars.next_if(|(_, c)| *c == '=').is_some() => TokenKind::GreaterOrEqual,
+            '!' if chars.next_if(|(_, c)| *c == '=').is_some() => TokenKind::NotEqual,
+            '<' => TokenKind::LessThan,
+            '>' => TokenKind::GreaterThan,
+            '=' => TokenKind::Equal,
+            c if c.is_ascii_alphabetic() => {
+                let mut identifier = c.to_string();
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_alphanumeric()) {
+                    identifier.push(c);
+                }
+                TokenKind::Identifier(identifier)
+            }
+            c if c.is_ascii_digit() => {
+                let mut number = c.to_string();
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    number.push(c);
+                }
+                TokenKind::Number(number)
             }
+            c => TokenKind::Unknown(c),
         };
-        let (statement, rest2) = rest.split_at(mid);
-        let rest2 = rest2.trim_start_matches(',');
-        let statement = statement.trim();
-        let statement = lex_statement(statement);
-        function.push(statement);
-        rest = rest2;
-        if rest.len() == 0 {
-            break;
-        }
+        let end = chars.peek().map_or(input.len(), |(i, _)| *i);
+        tokens.push(Token {
+            kind,
+            span: Span::new(start, end),
+        });
     }
-
-    function
+    tokens
 }
 
-fn lex_statement(statement: &str) -> Statement {
-    let second_char = match statement.chars().nth(1) {
-        Some(c) => c,
-        None => {
-            return lex_return(statement);
-        }
-    };
-    match second_char {
-        '<' | '>' | '=' => lex_comparator(statement),
-        _ => lex_return(statement),
-    }
-}
+#[cfg(test)]
+mod test {
+    use super::*;
 
-fn lex_comparator(statement: &str) -> Statement {
-    let mut chars = statement.chars();
-    let variable = match chars.next().unwrap() {
-        'a' => Variable::A,
-        'm' => Variable::M,
-        's' => Variable::S,
-        'x' => Variable::X,
-        _ => panic!("Invalid variable"),
-    };
-    let comparison = match chars.next().unwrap() {
-        '<' => BinaryComparison::LessThan,
-        '>' => BinaryComparison::GreaterThan,
-        _ => panic!("Invalid comparison"),
-    };
-    let (value, retur) = chars.as_str().split_at(chars.as_str().find(':').unwrap());
-    let value = value.parse::<u16>().unwrap();
-    let retur = retur.trim_start_matches(':');
+    #[test]
+    fn test_tokenize() {
+        let input = "px{a<2006:qkq,m>2090:A,rfg}";
+        let kinds: Vec<TokenKind> = tokenize(input).into_iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Identifier("px".to_string()),
+                TokenKind::LeftBrace,
+                TokenKind::Identifier("a".to_string()),
+                TokenKind::LessThan,
+                TokenKind::Number("2006".to_string()),
+                TokenKind::Colon,
+                TokenKind::Identifier("qkq".to_string()),
+                TokenKind::Comma,
+                TokenKind::Identifier("m".to_string()),
+                TokenKind::GreaterThan,
+                TokenKind::Number("2090".to_string()),
+                TokenKind::Colon,
+                TokenKind::Identifier("A".to_string()),
+                TokenKind::Comma,
+                TokenKind::Identifier("rfg".to_string()),
+                TokenKind::RightBrace,
+            ]
+        );
+    }
 
-    let return_value = match retur {
-        "A" => Return::Final(Result::Accept),
-        "R" => Return::Final(Result::Reject),
-        _ => Return::Function(retur.to_string()),
-    };
-    return Statement::Compare(Comparator {
-        variable,
-        comparison,
-        value,
-        return_value,
-    });
-}
+    #[test]
+    fn test_operators() {
+        let input = "a<b<=c>d>=e=f!=g!h";
+        let operators: Vec<TokenKind> = tokenize(input)
+            .into_iter()
+            .map(|t| t.kind)
+            .filter(|kind| !matches!(kind, TokenKind::Identifier(_)))
+            .collect();
+        assert_eq!(
+            operators,
+            vec![
+                TokenKind::LessThan,
+                TokenKind::LessOrEqual,
+                TokenKind::GreaterThan,
+                TokenKind::GreaterOrEqual,
+                TokenKind::Equal,
+                TokenKind::NotEqual,
+                TokenKind::Unknown('!'),
+            ]
+        );
+    }
 
-fn lex_return(statement: &str) -> Statement {
-    let mut chars = statement.chars();
-    let return_value = match chars.next().unwrap() {
-        'A' => Return::Final(Result::Accept),
-        'R' => Return::Final(Result::Reject),
-        _ => Return::Function(statement.to_string()),
-    };
-    Statement::Return(return_value)
-}
+    #[test]
+    fn test_spans() {
+        let input = "  lnx{m>1548:A,A}\n{x=787}";
+        let tokens = tokenize(input);
+        assert_eq!(tokens[0].span, Span::new(2, 5));
+        assert_eq!(tokens[4].span, Span::new(8, 12));
+        let newline = tokens
+            .iter()
+            .position(|t| t.kind == TokenKind::Newline)
+            .unwrap();
+        assert_eq!(tokens[newline].span, Span::new(17, 18));
+        assert_eq!(tokens[newline + 1].span, Span::new(18, 19));
+    }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    // one `{` per workflow
+    fn workflows(input: &str) -> usize {
+        let tokens = tokenize(input);
+        assert!(!tokens
+            .iter()
+            .any(|t| matches!(t.kind, TokenKind::Unknown(_))));
+        tokens
+            .iter()
+            .filter(|t| t.kind == TokenKind::LeftBrace)
+            .count()
+    }
 
     #[test]
     fn test_lex() {
         let input = "px{a<2006:qkq,m>2090:A,rfg}";
-        let map = lex_functions(input);
-        println!("{:?}", map);
-        assert_eq!(map.len(), 1);
+        assert_eq!(workflows(input), 1);
     }
 
     #[test]
     fn test_lex2() {
         let input = "lnx{m>1548:A,A}";
-        let map = lex_functions(input);
-        println!("{:?}", map);
-        assert_eq!(map.len(), 1);
+        assert_eq!(workflows(input), 1);
     }
 
     #[test]
@@ -171,7 +192,6 @@ mod test {
         qqz{s>2770:qs,m<1801:hdj,R}
         gd{a>3333:R,R}
         hdj{m>838:A,pv}";
-        let map = lex_functions(input);
-        assert_eq!(map.len(), 11);
+        assert_eq!(workflows(input), 11);
     }
 }
@@ -1,27 +1,46 @@
 use crate::{FunctionMap, Piece};
 
+pub mod diagnostic;
 mod lexer;
+mod parser;
+mod semantic;
 pub mod types;
 
+use diagnostic::Diagnostic;
+
 pub struct Compiled {
+    // names of the parts' values, in the order of `Piece`
+    pub variables: Vec<String>,
     pub pieces: Vec<Piece>,
     pub functions: FunctionMap,
 }
 
-pub fn compile_only_functions(input: &str) -> FunctionMap {
-    let mut blocks = input.split("\n\n");
-    let functions = blocks.next().unwrap();
-    lexer::lex_functions(functions)
-}
+/// Parses and checks the workflows and the parts.
+/// Every error and warning found is returned next to what could be compiled.
+pub fn analyze(input: &str) -> (Compiled, Vec<Diagnostic>) {
+    let (program, mut diagnostics) = parser::parse(input);
+    diagnostics.extend(semantic::check(&program));
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
 
-pub fn compile(input: &str) -> Compiled {
-    let mut blocks = input.split("\n\n");
-    let functions = blocks.next().unwrap();
-    let pieces = blocks.next().unwrap();
-    let functions = lexer::lex_functions(functions);
-    let pieces = lexer::lex_pieces(pieces);
+    let compiled = Compiled {
+        functions: program.functions(),
+        variables: program.variables,
+        pieces: program.pieces,
+    };
+    (compiled, diagnostics)
+}
 
-    Compiled { pieces, functions }
+/// Fails with the rendered errors, the warnings don't stop the compilation
+pub fn compile(input: &str) -> Result<Compiled, String> {
+    let (compiled, diagnostics) = analyze(input);
+    let errors: Vec<Diagnostic> = diagnostics
+        .into_iter()
+        .filter(|diagnostic| diagnostic.is_error())
+        .collect();
+    match errors.is_empty() {
+        true => Ok(compiled),
+        false => Err(diagnostic::render_all(&errors, input)),
+    }
 }
 
 #[cfg(test)]
@@ -31,6 +50,27 @@ mod test {
     #[test]
     fn test_compile() {
         let input = include_str!("../inputs/test.txt");
-        compile(input);
+        assert!(compile(input).is_ok());
+    }
+
+    #[test]
+    fn test_analyze_reports_parser_and_semantic_errors() {
+        let input = "in{s<1351:px,qqz}\npx{q<2006:qkq,A}\nqqz{s>2770:qs,R}\n\n{x=1,m=2,a=3,s=4}";
+        let (_, diagnostics) = analyze(input);
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "undefined variable `q`, the parts declare `x`, `m`, `a`, `s`",
+                "undefined workflow `qkq`",
+                "undefined workflow `qs`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compile_fails_with_the_errors() {
+        let errors = compile("in{s>2770:qs,R}").err().unwrap();
+        assert!(errors.contains("undefined workflow `qs`"));
     }
 }
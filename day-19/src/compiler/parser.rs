@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+
+use crate::{FunctionMap, Piece};
+
+use super::{
+    diagnostic::{Diagnostic, Span},
+    lexer::{self, Token, TokenKind},
+    types::{BinaryComparison, Comparator, Result, Return, Statement, Variable},
+};
+
+type ParseResult<T> = std::result::Result<T, Diagnostic>;
+
+pub struct Program {
+    pub workflows: Vec<Workflow>,
+    // names of the parts' values, declared by the part lines
+    pub variables: Vec<String>,
+    pub pieces: Vec<Piece>,
+}
+
+pub struct Workflow {
+    pub name: String,
+    // span of the name
+    pub span: Span,
+    pub rules: Vec<Rule>,
+}
+
+pub struct Rule {
+    pub statement: Statement,
+    pub span: Span,
+    // span of the workflow or result the rule sends the part to
+    pub target: Span,
+}
+
+impl Program {
+    /// The workflows without their spans, the first definition wins when a name is repeated
+    pub fn functions(&self) -> FunctionMap {
+        let mut map: FunctionMap = HashMap::new();
+        for workflow in &self.workflows {
+            map.entry(workflow.name.clone()).or_insert_with(|| {
+                workflow
+                    .rules
+                    .iter()
+                    .map(|rule| rule.statement.clone())
+                    .collect()
+            });
+        }
+        map
+    }
+}
+
+/// Parses the workflows and the parts after them.
+/// A line with an error is skipped so the following lines still get checked.
+pub fn parse(input: &str) -> (Program, Vec<Diagnostic>) {
+    let mut parser = Parser {
+        tokens: lexer::tokenize(input),
+        position: 0,
+        end: Span::new(input.len(), input.len()),
+        variables: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+    let mut program = Program {
+        workflows: Vec::new(),
+        variables: Vec::new(),
+        pieces: Vec::new(),
+    };
+
+    parser.skip_newlines();
+    // the workflows end on a blank line
+    while let Some(token) = parser.peek() {
+        if matches!(token.kind, TokenKind::Newline | TokenKind::LeftBrace) {
+            break;
+        }
+        if let Some(workflow) = parser.line(Parser::parse_workflow) {
+            program.workflows.push(workflow);
+        }
+    }
+
+    let mut values = Vec::new();
+    parser.skip_newlines();
+    while parser.peek().is_some() {
+        if let Some(piece) = parser.line(Parser::parse_piece) {
+            values.push(piece);
+        }
+        parser.skip_newlines();
+    }
+
+    // without parts, the variables are the ones the workflows compare
+    if parser.variables.is_empty() {
+        for workflow in &program.workflows {
+            for rule in &workflow.rules {
+                if let Statement::Compare(comparator) = &rule.statement {
+                    parser.declare(&comparator.variable.name);
+                }
+            }
+        }
+    }
+    program.variables = parser.variables;
+    // a variable missing from a part line is 0
+    program.pieces = values
+        .into_iter()
+        .map(|values| {
+            let mut piece = vec![0; program.variables.len()];
+            for (index, value) in values {
+                piece[index] = value;
+            }
+            piece
+        })
+        .collect();
+
+    let mut diagnostics = parser.diagnostics;
+    resolve_variables(&mut program, &mut diagnostics);
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+
+    (program, diagnostics)
+}
+
+// Points every comparison to the position of its variable in the parts
+fn resolve_variables(program: &mut Program, diagnostics: &mut Vec<Diagnostic>) {
+    let declared = program
+        .variables
+        .iter()
+        .map(|name| format!("`{}`", name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    for workflow in &mut program.workflows {
+        for rule in &mut workflow.rules {
+            let variable = match &mut rule.statement {
+                Statement::Compare(comparator) => &mut comparator.variable,
+                Statement::Return(_) => continue,
+            };
+            match program
+                .variables
+                .iter()
+                .position(|name| *name == variable.name)
+            {
+                Some(index) => variable.index = index,
+                None => diagnostics.push(Diagnostic::error(
+                    format!(
+                        "undefined variable `{}`, the parts declare {}",
+                        variable.name, declared
+                    ),
+                    Span::new(rule.span.start, rule.span.start + variable.name.len()),
+                )),
+            }
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    // where the input ends, for errors about missing tokens
+    end: Span,
+    variables: Vec<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn declare(&mut self, name: &str) -> usize {
+        match self.variables.iter().position(|variable| variable == name) {
+            Some(index) => index,
+            None => {
+                self.variables.push(name.to_string());
+                self.variables.len() - 1
+            }
+        }
+    }
+
+    fn skip_newlines(&mut self) {
+        while matches!(self.peek(), Some(token) if token.kind == TokenKind::Newline) {
+            self.position += 1;
+        }
+    }
+
+    fn closes_after_newlines(&self) -> bool {
+        let next = self.tokens[self.position..]
+            .iter()
+            .find(|token| token.kind != TokenKind::Newline);
+        next.is_some_and(|token| token.kind == TokenKind::RightBrace)
+    }
+
+    // Parses a whole line, on error the rest of the line is skipped
+    fn line<T>(&mut self, parse: fn(&mut Self) -> ParseResult<T>) -> Option<T> {
+        let result = parse(self).and_then(|value| {
+            self.expect_line_end()?;
+            Ok(value)
+        });
+        match result {
+            Ok(value) => Some(value),
+            Err(diagnostic) => {
+                self.diagnostics.push(diagnostic);
+                while let Some(token) = self.next() {
+                    if token.kind == TokenKind::Newline {
+                        break;
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Diagnostic {
+        match self.peek() {
+            Some(token) => Diagnostic::error(
+                format!("expected {}, found {}", expected, token.kind.describe()),
+                token.span,
+            ),
+            None => Diagnostic::error(
+                format!("expected {}, found end of input", expected),
+                self.end,
+            ),
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> ParseResult<Span> {
+        match self.peek() {
+            Some(token) if token.kind == kind => Ok(self.next().unwrap().span),
+            _ => Err(self.unexpected(&kind.describe())),
+        }
+    }
+
+    fn expect_identifier(&mut self, expected: &str) -> ParseResult<(String, Span)> {
+        match self.peek() {
+            Some(Token {
+                kind: TokenKind::Identifier(identifier),
+                span,
+            }) => {
+                let identifier = (identifier.clone(), *span);
+                self.position += 1;
+                Ok(identifier)
+            }
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    fn expect_value(&mut self) -> ParseResult<u64> {
+        match self.peek() {
+            Some(Token {
+                kind: TokenKind::Number(number),
+                span,
+            }) => {
+                let value = number.parse::<u64>().map_err(|_| {
+                    Diagnostic::error(
+                        format!("value `{}` is too large, the limit is {}", number, u64::MAX),
+                        *span,
+                    )
+                })?;
+                self.position += 1;
+                Ok(value)
+            }
+            _ => Err(self.unexpected("a number")),
+        }
+    }
+
+    fn expect_line_end(&mut self) -> ParseResult<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) if token.kind == TokenKind::Newline => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(_) => Err(self.unexpected("end of line")),
+        }
+    }
+
+    // px{a<2006:qkq,m>2090:A,rfg}, the rules can also go on their own lines
+    fn parse_workflow(&mut self) -> ParseResult<Workflow> {
+        let (name, span) = self.expect_identifier("a workflow name")?;
+        self.expect(TokenKind::LeftBrace)?;
+        self.skip_newlines();
+        let mut rules = Vec::new();
+        loop {
+            rules.push(self.parse_rule()?);
+            match self.peek().map(|token| &token.kind) {
+                Some(TokenKind::Comma) => {
+                    self.position += 1;
+                    self.skip_newlines();
+                }
+                // the `}` on its own line
+                Some(TokenKind::Newline) if self.closes_after_newlines() => {
+                    self.skip_newlines();
+                    self.position += 1;
+                    break;
+                }
+                Some(TokenKind::RightBrace) => {
+                    self.position += 1;
+                    break;
+                }
+                _ => return Err(self.unexpected("`,` or `}`")),
+            }
+        }
+        Ok(Workflow { name, span, rules })
+    }
+
+    // a<2006:qkq or rfg
+    fn parse_rule(&mut self) -> ParseResult<Rule> {
+        let (first, first_span) = self.expect_identifier("a rule")?;
+        let comparison = match self.peek().map(|token| &token.kind) {
+            Some(TokenKind::LessThan) => BinaryComparison::LessThan,
+            Some(TokenKind::GreaterThan) => BinaryComparison::GreaterThan,
+            Some(TokenKind::Equal) => BinaryComparison::Equal,
+            Some(TokenKind::NotEqual) => BinaryComparison::NotEqual,
+            Some(TokenKind::LessOrEqual) => BinaryComparison::LessOrEqual,
+            Some(TokenKind::GreaterOrEqual) => BinaryComparison::GreaterOrEqual,
+            _ => {
+                return Ok(Rule {
+                    statement: Statement::Return(return_value(&first)),
+                    span: first_span,
+                    target: first_span,
+                })
+            }
+        };
+        self.position += 1;
+        let value = self.expect_value()?;
+        self.expect(TokenKind::Colon)?;
+        let (target, target_span) = self.expect_identifier("a target workflow")?;
+        Ok(Rule {
+            statement: Statement::Compare(Comparator {
+                // resolved once the part lines declared every variable
+                variable: Variable {
+                    index: 0,
+                    name: first,
+                },
+                comparison,
+                value,
+                return_value: return_value(&target),
+            }),
+            span: first_span.to(target_span),
+            target: target_span,
+        })
+    }
+
+    // {x=787,m=2655,a=1222,s=2876}, the values by the position of their variable
+    fn parse_piece(&mut self) -> ParseResult<Vec<(usize, u64)>> {
+        let mut values: Vec<(usize, u64)> = Vec::new();
+        self.expect(TokenKind::LeftBrace)?;
+        loop {
+            let (name, span) = self.expect_identifier("a variable")?;
+            self.expect(TokenKind::Equal)?;
+            let value = self.expect_value()?;
+            let index = self.declare(&name);
+            if values.iter().any(|(set, _)| *set == index) {
+                return Err(Diagnostic::error(
+                    format!("variable `{}` is set more than once", name),
+                    span,
+                ));
+            }
+            values.push((index, value));
+            match self.peek().map(|token| &token.kind) {
+                Some(TokenKind::Comma) => self.position += 1,
+                Some(TokenKind::RightBrace) => {
+                    self.position += 1;
+                    break;
+                }
+                _ => return Err(self.unexpected("`,` or `}`")),
+            }
+        }
+        Ok(values)
+    }
+}
+
+fn return_value(target: &str) -> Return {
+    match target {
+        "A" => Return::Final(Result::Accept),
+        "R" => Return::Final(Result::Reject),
+        _ => Return::Function(target.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn messages(input: &str) -> Vec<String> {
+        let (_, diagnostics) = parse(input);
+        diagnostics.into_iter().map(|d| d.message).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = "px{a<2006:qkq,m>2090:A,rfg}";
+        let (program, diagnostics) = parse(input);
+        assert!(diagnostics.is_empty());
+        let map = program.functions();
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["px"].len(), 3);
+    }
+
+    #[test]
+    fn test_parse2() {
+        let input = "lnx{m>1548:A,A}";
+        let (program, diagnostics) = parse(input);
+        assert!(diagnostics.is_empty());
+        assert_eq!(program.functions().len(), 1);
+    }
+
+    #[test]
+    fn test_multiple_functions() {
+        let input = "px{a<2006:qkq,m>2090:A,rfg}
+        pv{a>1716:R,A}
+        lnx{m>1548:A,A}
+        rfg{s<537:gd,x>2440:R,A}
+        qs{s>3448:A,lnx}
+        qkq{x<1416:A,crn}
+        crn{x>2662:A,R}
+        in{s<1351:px,qqz}
+        qqz{s>2770:qs,m<1801:hdj,R}
+        gd{a>3333:R,R}
+        hdj{m>838:A,pv}";
+        let (program, diagnostics) = parse(input);
+        assert!(diagnostics.is_empty());
+        assert_eq!(program.functions().len(), 11);
+    }
+
+    #[test]
+    fn test_pieces() {
+        let input = include_str!("../inputs/test.txt");
+        let (program, diagnostics) = parse(input);
+        assert!(diagnostics.is_empty());
+        assert_eq!(program.pieces.len(), 5);
+        assert_eq!(program.pieces[0], [787, 2655, 1222, 2876]);
+    }
+
+    #[test]
+    fn test_rule_spans() {
+        let input = "px{a<2006:qkq,rfg}";
+        let (program, _) = parse(input);
+        let rules = &program.workflows[0].rules;
+        assert_eq!(program.workflows[0].span, Span::new(0, 2));
+        assert_eq!(rules[0].span, Span::new(3, 13));
+        assert_eq!(rules[0].target, Span::new(10, 13));
+        assert_eq!(rules[1].span, Span::new(14, 17));
+    }
+
+    #[test]
+    fn test_collects_every_error() {
+        let input = "px{q<2006:qkq,rfg}
+pv{a=1716:R,A}
+lnx{m>1548A,A}
+qs{s>99999999999999999999:A,lnx
+in{s<1351:px,qqz}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,x=44}
+{x:1679}";
+        assert_eq!(
+            messages(input),
+            vec![
+                "undefined variable `q`, the parts declare `x`, `m`, `a`, `s`",
+                "expected `:`, found `A`",
+                "value `99999999999999999999` is too large, the limit is 18446744073709551615",
+                "variable `x` is set more than once",
+                "expected `=`, found `:`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_variables_and_operators() {
+        let input = "in{weight>=100000000000:R,color!=3:R,size<=2:A,color=3:A,R}
+
+{color=3,weight=7}
+{size=1}";
+        let (program, diagnostics) = parse(input);
+        assert!(diagnostics.is_empty());
+        assert_eq!(program.variables, vec!["color", "weight", "size"]);
+        assert_eq!(program.pieces, vec![vec![3, 7, 0], vec![0, 0, 1]]);
+        let rules: Vec<String> = program.workflows[0]
+            .rules
+            .iter()
+            .map(|rule| rule.statement.to_string())
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                "weight>=100000000000:R",
+                "color!=3:R",
+                "size<=2:A",
+                "color=3:A",
+                "R"
+            ]
+        );
+        match &program.workflows[0].rules[0].statement {
+            Statement::Compare(comparator) => assert_eq!(comparator.variable.index, 1),
+            Statement::Return(_) => panic!("expected a comparison"),
+        }
+    }
+
+    #[test]
+    fn test_variables_without_parts() {
+        let (program, diagnostics) = parse("in{m<10:A,x>5:R,m>2:A,R}");
+        assert!(diagnostics.is_empty());
+        assert_eq!(program.variables, vec!["m", "x"]);
+    }
+
+    #[test]
+    fn test_rule_per_line() {
+        let (program, diagnostics) = parse("in{\n    s<1351:px,\n    A\n}\npx{R}\n\n{s=1}");
+        assert!(diagnostics.is_empty());
+        assert_eq!(program.workflows.len(), 2);
+        assert_eq!(program.workflows[0].rules.len(), 2);
+        // the next lines are parsed as workflows of their own after the error
+        assert_eq!(
+            messages("in{\n    s<1351:px\n    A\n}")[0],
+            "expected `,` or `}`, found end of line"
+        );
+    }
+
+    #[test]
+    fn test_missing_brace() {
+        assert_eq!(
+            messages("in{s<1351:px,qqz"),
+            vec!["expected `,` or `}`, found end of input"]
+        );
+        assert_eq!(
+            messages("in s<1351:px,qqz}"),
+            vec!["expected `{`, found `s`"]
+        );
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::{
+    diagnostic::{Diagnostic, Span},
+    parser::{Program, Workflow},
+    types::{Return, Statement, ValueRange},
+};
+use crate::{
+    interpreter::range::{self, Batch, Branch, Visitor},
+    FunctionMap,
+};
+
+/// Checks the parsed program for the problems the parser can't see:
+/// repeated or undefined workflows, a missing `in`, workflows without a fallthrough,
+/// unreachable rules and workflows, cycles that would never terminate,
+/// and variables too many for the parts to be counted
+pub fn check(program: &Program) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let mut workflows: HashMap<&str, &Workflow> = HashMap::new();
+    for workflow in &program.workflows {
+        if workflows.contains_key(workflow.name.as_str()) {
+            diagnostics.push(Diagnostic::error(
+                format!("workflow `{}` is defined more than once", workflow.name),
+                workflow.span,
+            ));
+            continue;
+        }
+        workflows.insert(&workflow.name, workflow);
+    }
+
+    for workflow in &program.workflows {
+        for rule in &workflow.rules {
+            if let Some(target) = target(&rule.statement) {
+                if !workflows.contains_key(target) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("undefined workflow `{}`", target),
+                        rule.target,
+                    ));
+                }
+            }
+        }
+        check_rules(workflow, &mut diagnostics);
+    }
+
+    match workflows.contains_key("in") {
+        true => check_reachable(program, &workflows, &mut diagnostics),
+        // nothing to point at, the error goes to the start of the input
+        false => diagnostics.push(Diagnostic::error(
+            "missing the `in` workflow, every part starts there".to_string(),
+            Span::new(0, 0),
+        )),
+    }
+
+    check_cycles(program, &workflows, &mut diagnostics);
+
+    // only counting the accepted parts fails, the parts can still be evaluated
+    if let Err(message) = range::check_count(program.variables.len()) {
+        diagnostics.push(Diagnostic::warning(message, Span::new(0, 0)));
+    }
+
+    diagnostics
+}
+
+fn target(statement: &Statement) -> Option<&str> {
+    let return_value = match statement {
+        Statement::Compare(comparator) => &comparator.return_value,
+        Statement::Return(return_value) => return_value,
+    };
+    match return_value {
+        Return::Function(name) => Some(name),
+        Return::Final(_) => None,
+    }
+}
+
+// Follows the values each variable can still have after every rule of the workflow,
+// a rule that no value can get to is unreachable
+fn check_rules(workflow: &Workflow, diagnostics: &mut Vec<Diagnostic>) {
+    // a variable missing from the map can still have any value
+    let mut ranges: HashMap<&str, Vec<ValueRange>> = HashMap::new();
+    for (i, rule) in workflow.rules.iter().enumerate() {
+        let comparator = match &rule.statement {
+            Statement::Compare(comparator) => comparator,
+            Statement::Return(_) => {
+                if let Some(next) = workflow.rules.get(i + 1) {
+                    let last = workflow.rules.last().unwrap();
+                    diagnostics.push(Diagnostic::warning(
+                        format!("unreachable rules, `{}` always matches", rule.statement),
+                        next.span.to(last.span),
+                    ));
+                }
+                return;
+            }
+        };
+
+        let current = ranges
+            .remove(comparator.variable.name.as_str())
+            .unwrap_or_else(|| vec![ValueRange::new(0, u64::MAX)]);
+        let mut matched = Vec::new();
+        let mut remaining = Vec::new();
+        for range in &current {
+            let (passing, failing) = comparator.split(range);
+            matched.extend(passing);
+            remaining.extend(failing);
+        }
+        if matched.is_empty() {
+            diagnostics.push(Diagnostic::warning(
+                format!("unreachable rule, `{}` can never match", rule.statement),
+                rule.span,
+            ));
+        }
+        if remaining.is_empty() {
+            // every value matched, nothing falls through to the next rules
+            if let Some(next) = workflow.rules.get(i + 1) {
+                let last = workflow.rules.last().unwrap();
+                diagnostics.push(Diagnostic::warning(
+                    "unreachable rules, the rules before them match every part".to_string(),
+                    next.span.to(last.span),
+                ));
+            }
+            return;
+        }
+        ranges.insert(&comparator.variable.name, remaining);
+    }
+
+    diagnostics.push(Diagnostic::error(
+        format!(
+            "workflow `{}` has no fallthrough rule, its last rule needs no condition",
+            workflow.name
+        ),
+        workflow.span,
+    ));
+}
+
+fn check_reachable(
+    program: &Program,
+    workflows: &HashMap<&str, &Workflow>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut reached = HashSet::from(["in"]);
+    let mut queue = VecDeque::from(["in"]);
+    while let Some(name) = queue.pop_front() {
+        for rule in &workflows[name].rules {
+            if let Some(target) = target(&rule.statement) {
+                if workflows.contains_key(target) && reached.insert(target) {
+                    queue.push_back(target);
+                }
+            }
+        }
+    }
+
+    for workflow in &program.workflows {
+        if !reached.contains(workflow.name.as_str()) {
+            diagnostics.push(Diagnostic::warning(
+                format!("workflow `{}` is never reached from `in`", workflow.name),
+                workflow.span,
+            ));
+        }
+    }
+}
+
+// Collects the workflows of every cycle some parts go round
+struct CycleFinder {
+    cycles: Vec<Vec<String>>,
+}
+
+impl Visitor for CycleFinder {
+    fn cycle(&mut self, workflow: &str, _batch: &Batch, path: &[Branch]) {
+        let start = path
+            .iter()
+            .position(|branch| branch.workflow == workflow)
+            .unwrap();
+        let mut cycle: Vec<String> = Vec::new();
+        for branch in &path[start..] {
+            if cycle.last().map(String::as_str) != Some(branch.workflow) {
+                cycle.push(branch.workflow.to_string());
+            }
+        }
+        self.cycles.push(cycle);
+    }
+}
+
+// The walker tells the variables apart by their index, which the parser leaves at 0
+// for the undeclared ones, so every name gets its own index here
+fn functions_by_name(program: &Program) -> (FunctionMap, usize) {
+    let mut names = program.variables.clone();
+    let mut functions = program.functions();
+    for statement in functions.values_mut().flatten() {
+        if let Statement::Compare(comparator) = statement {
+            let variable = &mut comparator.variable;
+            variable.index = match names.iter().position(|name| *name == variable.name) {
+                Some(index) => index,
+                None => {
+                    names.push(variable.name.clone());
+                    names.len() - 1
+                }
+            };
+        }
+    }
+    (functions, names.len())
+}
+
+// Only the cycles that some parts can go all the way round are reported,
+// a graph cycle whose conditions exclude each other is fine
+fn check_cycles(
+    program: &Program,
+    workflows: &HashMap<&str, &Workflow>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let (functions, variables) = functions_by_name(program);
+    let mut finder = CycleFinder { cycles: Vec::new() };
+    range::walk(&functions, Batch::any(variables), &mut finder);
+
+    let mut reported = HashSet::new();
+    for cycle in finder.cycles {
+        // the same cycle can be found from any of its workflows
+        let mut key = cycle.clone();
+        key.sort();
+        if !reported.insert(key) {
+            continue;
+        }
+        let first = cycle
+            .iter()
+            .map(|name| workflows[name.as_str()])
+            .min_by_key(|workflow| workflow.span.start)
+            .unwrap();
+        let start = cycle.iter().position(|name| *name == first.name).unwrap();
+        let mut path: Vec<&str> = cycle[start..]
+            .iter()
+            .chain(&cycle[..start])
+            .map(String::as_str)
+            .collect();
+        path.push(&first.name);
+        diagnostics.push(Diagnostic::error(
+            format!("workflows never terminate: {}", path.join(" -> ")),
+            first.span,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::compiler::{diagnostic::Severity, parser};
+
+    use super::*;
+
+    fn diagnostics(input: &str) -> Vec<(Severity, String)> {
+        let (program, diagnostics) = parser::parse(input);
+        assert!(diagnostics.is_empty());
+        check(&program)
+            .into_iter()
+            .map(|d| (d.severity, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_example_is_clean() {
+        let input = include_str!("../inputs/test.txt");
+        let (program, _) = parser::parse(input);
+        assert_eq!(check(&program), vec![]);
+    }
+
+    #[test]
+    fn test_undefined_workflow() {
+        let input = "in{s<1351:px,qqz}\npx{a<2006:qkx,A}\nqqz{R}";
+        let (program, _) = parser::parse(input);
+        let diagnostics = check(&program);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "undefined workflow `qkx`");
+        assert_eq!(diagnostics[0].span, Span::new(28, 31));
+    }
+
+    #[test]
+    fn test_missing_in() {
+        assert_eq!(
+            diagnostics("px{a<2006:A,R}"),
+            vec![(
+                Severity::Error,
+                "missing the `in` workflow, every part starts there".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_unreachable_rules() {
+        assert_eq!(
+            diagnostics("in{x>10:A,x>20:R,x<5:R,R}"),
+            vec![(
+                Severity::Warning,
+                "unreachable rule, `x>20:R` can never match".to_string()
+            )]
+        );
+        assert_eq!(
+            diagnostics("in{x<10:A,x>9:R,A,R}"),
+            vec![(
+                Severity::Warning,
+                "unreachable rules, the rules before them match every part".to_string()
+            )]
+        );
+        assert_eq!(
+            diagnostics("in{A,x>9:R,R}"),
+            vec![(
+                Severity::Warning,
+                "unreachable rules, `A` always matches".to_string()
+            )]
+        );
+        assert_eq!(
+            diagnostics("in{A}\nfoo{R}"),
+            vec![(
+                Severity::Warning,
+                "workflow `foo` is never reached from `in`".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_unreachable_with_equality() {
+        assert_eq!(
+            diagnostics("in{x!=5:A,x=5:R,x>=0:R,R}"),
+            vec![(
+                Severity::Warning,
+                "unreachable rules, the rules before them match every part".to_string()
+            )]
+        );
+        assert_eq!(
+            diagnostics("in{x<=5:A,x=3:R,R}"),
+            vec![(
+                Severity::Warning,
+                "unreachable rule, `x=3:R` can never match".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_no_fallthrough() {
+        assert_eq!(
+            diagnostics("in{x>10:A}"),
+            vec![(
+                Severity::Error,
+                "workflow `in` has no fallthrough rule, its last rule needs no condition"
+                    .to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        assert_eq!(
+            diagnostics("in{x>10:a,R}\na{m<5:b,A}\nb{in}"),
+            vec![(
+                Severity::Error,
+                "workflows never terminate: in -> a -> b -> in".to_string()
+            )]
+        );
+        assert_eq!(
+            diagnostics("in{x>10:in,R}"),
+            vec![(
+                Severity::Error,
+                "workflows never terminate: in -> in".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_too_many_variables_to_count() {
+        let input = "in{a>1:R,b>1:R,c>1:R,d>1:R,e>1:R,f>1:R,g>1:R,h>1:R,i>1:R,j>1:R,k>1:R,A}";
+        assert_eq!(
+            diagnostics(input),
+            vec![(
+                Severity::Warning,
+                "the parts of 11 variables are too many to count, 4000^11 doesn't fit in 128 bits"
+                    .to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_cycle_no_part_goes_round() {
+        // the parts sent back to `in` are under 5, they never get past `x>10`
+        assert_eq!(diagnostics("in{x>10:a,R}\na{x<5:in,A}"), vec![]);
+        assert_eq!(
+            diagnostics("in{x>10:a,R}\na{m<5:in,A}"),
+            vec![(
+                Severity::Error,
+                "workflows never terminate: in -> a -> in".to_string()
+            )]
+        );
+    }
+}
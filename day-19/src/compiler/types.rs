@@ -1,27 +1,86 @@
-#[derive(Debug, Clone, Copy)]
-pub enum Variable {
-    X = 0,
-    M = 1,
-    A = 2,
-    S = 3,
-}
-
-impl Variable {
-    pub fn from_char(c: char) -> Variable {
-        match c {
-            'a' => Variable::A,
-            'm' => Variable::M,
-            's' => Variable::S,
-            'x' => Variable::X,
-            _ => panic!("Invalid variable"),
-        }
-    }
+use std::fmt::{self, Display};
+
+use crate::Piece;
+
+/// An attribute of the parts, `index` is its position in the parts' values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    pub index: usize,
+    pub name: String,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BinaryComparison {
     LessThan,
     GreaterThan,
+    Equal,
+    NotEqual,
+    LessOrEqual,
+    GreaterOrEqual,
+}
+
+impl BinaryComparison {
+    pub fn compare(self, left: u64, right: u64) -> bool {
+        match self {
+            BinaryComparison::LessThan => left < right,
+            BinaryComparison::GreaterThan => left > right,
+            BinaryComparison::Equal => left == right,
+            BinaryComparison::NotEqual => left != right,
+            BinaryComparison::LessOrEqual => left <= right,
+            BinaryComparison::GreaterOrEqual => left >= right,
+        }
+    }
+
+    /// The comparison that passes exactly the values this one fails
+    pub fn negate(self) -> Self {
+        match self {
+            BinaryComparison::LessThan => BinaryComparison::GreaterOrEqual,
+            BinaryComparison::GreaterThan => BinaryComparison::LessOrEqual,
+            BinaryComparison::Equal => BinaryComparison::NotEqual,
+            BinaryComparison::NotEqual => BinaryComparison::Equal,
+            BinaryComparison::LessOrEqual => BinaryComparison::GreaterThan,
+            BinaryComparison::GreaterOrEqual => BinaryComparison::LessThan,
+        }
+    }
+
+    /// The values that pass the comparison against `value`
+    pub fn passing(self, value: u64) -> Vec<ValueRange> {
+        let below = value.checked_sub(1).map(|end| ValueRange::new(0, end));
+        let above = value
+            .checked_add(1)
+            .map(|start| ValueRange::new(start, u64::MAX));
+        match self {
+            BinaryComparison::LessThan => below.into_iter().collect(),
+            BinaryComparison::GreaterThan => above.into_iter().collect(),
+            BinaryComparison::Equal => vec![ValueRange::new(value, value)],
+            BinaryComparison::NotEqual => below.into_iter().chain(above).collect(),
+            BinaryComparison::LessOrEqual => vec![ValueRange::new(0, value)],
+            BinaryComparison::GreaterOrEqual => vec![ValueRange::new(value, u64::MAX)],
+        }
+    }
+}
+
+/// Values from `start` to `end`, both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ValueRange {
+    pub fn new(start: u64, end: u64) -> Self {
+        Self { start, end }
+    }
+
+    pub fn size(&self) -> u128 {
+        (self.end - self.start) as u128 + 1
+    }
+
+    pub fn intersection(&self, other: &ValueRange) -> Option<ValueRange> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        (start <= end).then(|| ValueRange::new(start, end))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -30,24 +89,174 @@ pub enum Result {
     Reject,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Return {
     Function(String),
     Final(Result),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Comparator {
     pub variable: Variable,
     pub comparison: BinaryComparison,
-    pub value: u16,
+    pub value: u64,
     pub return_value: Return,
 }
 
-#[derive(Debug, Clone)]
+impl Comparator {
+    pub fn matches(&self, piece: &Piece) -> bool {
+        let variable_value = piece[self.variable.index];
+        self.comparison.compare(variable_value, self.value)
+    }
+
+    /// Splits `range` into the values that match the comparator and the ones that don't
+    pub fn split(&self, range: &ValueRange) -> (Vec<ValueRange>, Vec<ValueRange>) {
+        let intersect = |comparison: BinaryComparison| {
+            comparison
+                .passing(self.value)
+                .iter()
+                .filter_map(|passing| passing.intersection(range))
+                .collect()
+        };
+        (
+            intersect(self.comparison),
+            intersect(self.comparison.negate()),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Statement {
     Compare(Comparator),
     Return(Return),
 }
 
+impl Statement {
+    /// Where the statement sends the parts it matches
+    pub fn return_value(&self) -> &Return {
+        match self {
+            Statement::Compare(comparator) => &comparator.return_value,
+            Statement::Return(return_value) => return_value,
+        }
+    }
+}
+
 pub type Function = Vec<Statement>;
+
+impl Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl Display for BinaryComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = match self {
+            BinaryComparison::LessThan => "<",
+            BinaryComparison::GreaterThan => ">",
+            BinaryComparison::Equal => "=",
+            BinaryComparison::NotEqual => "!=",
+            BinaryComparison::LessOrEqual => "<=",
+            BinaryComparison::GreaterOrEqual => ">=",
+        };
+        write!(f, "{}", operator)
+    }
+}
+
+impl Display for Result {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Result::Accept => 'A',
+            Result::Reject => 'R',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+impl Display for Return {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Return::Function(name) => write!(f, "{}", name),
+            Return::Final(result) => write!(f, "{}", result),
+        }
+    }
+}
+
+impl Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}:{}",
+            self.variable, self.comparison, self.value, self.return_value
+        )
+    }
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Compare(comparator) => write!(f, "{}", comparator),
+            Statement::Return(return_value) => write!(f, "{}", return_value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn comparator(comparison: BinaryComparison, value: u64) -> Comparator {
+        Comparator {
+            variable: Variable {
+                index: 0,
+                name: "x".to_string(),
+            },
+            comparison,
+            value,
+            return_value: Return::Final(Result::Accept),
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let range = ValueRange::new(1, 4000);
+        let split = |comparison, value| comparator(comparison, value).split(&range);
+        assert_eq!(
+            split(BinaryComparison::LessThan, 1000),
+            (
+                vec![ValueRange::new(1, 999)],
+                vec![ValueRange::new(1000, 4000)]
+            )
+        );
+        assert_eq!(
+            split(BinaryComparison::GreaterOrEqual, 1000),
+            (
+                vec![ValueRange::new(1000, 4000)],
+                vec![ValueRange::new(1, 999)]
+            )
+        );
+        assert_eq!(
+            split(BinaryComparison::NotEqual, 1000),
+            (
+                vec![ValueRange::new(1, 999), ValueRange::new(1001, 4000)],
+                vec![ValueRange::new(1000, 1000)]
+            )
+        );
+        assert_eq!(
+            split(BinaryComparison::GreaterThan, 5000),
+            (vec![], vec![ValueRange::new(1, 4000)])
+        );
+        assert_eq!(
+            split(BinaryComparison::LessThan, 0),
+            (vec![], vec![ValueRange::new(1, 4000)])
+        );
+    }
+
+    #[test]
+    fn test_matches_wide_values() {
+        let piece = vec![u64::MAX];
+        assert!(comparator(BinaryComparison::Equal, u64::MAX).matches(&piece));
+        assert!(!comparator(BinaryComparison::GreaterThan, u64::MAX).matches(&piece));
+        assert!(comparator(BinaryComparison::GreaterOrEqual, 1 << 40).matches(&piece));
+    }
+}
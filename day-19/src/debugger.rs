@@ -0,0 +1,227 @@
+use crate::{
+    compiler::{self, types::Statement, Compiled},
+    interpreter::{
+        self,
+        range::{self, Batch, Visitor},
+    },
+    FunctionMap, Piece,
+};
+
+pub struct Workflow {
+    pub name: String,
+    // rules written as in the input
+    pub rules: Vec<String>,
+}
+
+pub struct Step {
+    pub workflow: String,
+    // index of the rule being tested
+    pub rule: usize,
+    pub matched: bool,
+    // where the rule sends the part when it matches
+    pub target: String,
+}
+
+pub struct PieceTrace {
+    pub piece: String,
+    pub steps: Vec<Step>,
+    pub accepted: bool,
+}
+
+// (start, end) of every variable, both ends included
+pub type Ranges = Vec<(u64, u64)>;
+
+pub struct RangeStep {
+    pub workflow: String,
+    pub rule: usize,
+    pub ranges: Ranges,
+    // the parts of the ranges sent to the target
+    pub matched: Vec<Ranges>,
+    // the parts of the ranges left for the next rule
+    pub unmatched: Vec<Ranges>,
+    pub target: String,
+}
+
+/// The input compiled once for every trace of the debugger
+pub struct Program {
+    compiled: Compiled,
+}
+
+impl Program {
+    /// Fails with the rendered compiler errors
+    pub fn compile(input: &str) -> Result<Self, String> {
+        let compiled = compiler::compile(input)?;
+        Ok(Program { compiled })
+    }
+
+    /// Names of the parts' values, in the order of the ranges
+    pub fn variables(&self) -> &[String] {
+        &self.compiled.variables
+    }
+
+    /// Every workflow of the input, sorted by name
+    pub fn workflows(&self) -> Vec<Workflow> {
+        let mut workflows: Vec<Workflow> = self
+            .compiled
+            .functions
+            .iter()
+            .map(|(name, function)| Workflow {
+                name: name.clone(),
+                rules: function.iter().map(|rule| rule.to_string()).collect(),
+            })
+            .collect();
+        workflows.sort_by(|a, b| a.name.cmp(&b.name));
+        workflows
+    }
+
+    /// The rules each part goes through in `interpreter::evaluate_piece`, in order
+    pub fn trace_pieces(&self) -> Vec<PieceTrace> {
+        self.compiled
+            .pieces
+            .iter()
+            .map(|piece| trace_piece(piece, &self.compiled.variables, &self.compiled.functions))
+            .collect()
+    }
+
+    /// How `interpreter::range` splits the ranges on every rule, in the order it visits them
+    pub fn trace_ranges(&self) -> Vec<RangeStep> {
+        let mut tracer = RangeTracer { steps: Vec::new() };
+        range::walk(
+            &self.compiled.functions,
+            Batch::full(self.compiled.variables.len()),
+            &mut tracer,
+        );
+        tracer.steps
+    }
+}
+
+fn trace_piece(piece: &Piece, variables: &[String], functions: &FunctionMap) -> PieceTrace {
+    let mut steps = Vec::new();
+    let result = interpreter::evaluate_piece(piece, functions, |workflow, rule, matched| {
+        steps.push(Step {
+            workflow: workflow.to_string(),
+            rule,
+            matched,
+            target: functions[workflow][rule].return_value().to_string(),
+        });
+    });
+    let values: Vec<String> = variables
+        .iter()
+        .zip(piece)
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect();
+    PieceTrace {
+        piece: format!("{{{}}}", values.join(",")),
+        steps,
+        accepted: result == compiler::types::Result::Accept,
+    }
+}
+
+struct RangeTracer {
+    steps: Vec<RangeStep>,
+}
+
+impl Visitor for RangeTracer {
+    fn rule(
+        &mut self,
+        workflow: &str,
+        rule: usize,
+        statement: &Statement,
+        batch: &Batch,
+        matched: &[Batch],
+        unmatched: &[Batch],
+    ) {
+        self.steps.push(RangeStep {
+            workflow: workflow.to_string(),
+            rule,
+            ranges: ranges(batch),
+            matched: matched.iter().map(ranges).collect(),
+            unmatched: unmatched.iter().map(ranges).collect(),
+            target: statement.return_value().to_string(),
+        });
+    }
+}
+
+fn ranges(batch: &Batch) -> Ranges {
+    batch
+        .ranges
+        .iter()
+        .map(|range| (range.start, range.end))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_workflows() {
+        let input = include_str!("./inputs/test.txt");
+        let workflows = Program::compile(input).unwrap().workflows();
+        assert_eq!(workflows.len(), 11);
+        let px = workflows.iter().find(|w| w.name == "px").unwrap();
+        assert_eq!(px.rules, vec!["a<2006:qkq", "m>2090:A", "rfg"]);
+    }
+
+    #[test]
+    fn test_trace_pieces() {
+        let input = include_str!("./inputs/test.txt");
+        let traces = Program::compile(input).unwrap().trace_pieces();
+        let accepted: Vec<bool> = traces.iter().map(|trace| trace.accepted).collect();
+        assert_eq!(accepted, vec![true, false, true, false, true]);
+
+        let first = &traces[0];
+        assert_eq!(first.piece, "{x=787,m=2655,a=1222,s=2876}");
+        let path: Vec<(&str, usize, bool)> = first
+            .steps
+            .iter()
+            .map(|step| (step.workflow.as_str(), step.rule, step.matched))
+            .collect();
+        assert_eq!(
+            path,
+            vec![
+                ("in", 0, false),
+                ("in", 1, true),
+                ("qqz", 0, true),
+                ("qs", 0, false),
+                ("qs", 1, true),
+                ("lnx", 0, true),
+            ]
+        );
+        assert_eq!(first.steps.last().unwrap().target, "A");
+    }
+
+    #[test]
+    fn test_trace_ranges_with_not_equal() {
+        let steps = Program::compile("in{x!=10:A,R}\n\n{x=1}")
+            .unwrap()
+            .trace_ranges();
+        assert_eq!(steps[0].matched, vec![vec![(1, 9)], vec![(11, 4000)]]);
+        assert_eq!(steps[0].unmatched, vec![vec![(10, 10)]]);
+        assert_eq!(steps[1].rule, 1);
+        assert_eq!(steps[1].ranges, vec![(10, 10)]);
+    }
+
+    #[test]
+    fn test_trace_ranges() {
+        let input = include_str!("./inputs/test.txt");
+        let steps = Program::compile(input).unwrap().trace_ranges();
+        let first = &steps[0];
+        assert_eq!(first.workflow, "in");
+        assert_eq!(first.matched[0][3], (1, 1350));
+        assert_eq!(first.unmatched[0][3], (1351, 4000));
+
+        let accepted: u64 = steps
+            .iter()
+            .filter(|step| step.target == "A")
+            .flat_map(|step| &step.matched)
+            .map(|ranges| {
+                ranges
+                    .iter()
+                    .map(|(start, end)| end - start + 1)
+                    .product::<u64>()
+            })
+            .sum();
+        assert_eq!(accepted, 167_409_079_868_000);
+    }
+}
@@ -0,0 +1,9 @@
+use crate::compiler;
+
+pub use crate::compiler::diagnostic::{render_all, Diagnostic, Severity, Span};
+
+/// Every error and warning found in the workflows and parts, in source order
+pub fn check(input: &str) -> Vec<Diagnostic> {
+    let (_, diagnostics) = compiler::analyze(input);
+    diagnostics
+}
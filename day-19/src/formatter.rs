@@ -0,0 +1,137 @@
+use crate::{compiler, FunctionMap, Piece};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Options {
+    // every rule on its own indented line, instead of the whole workflow on one line
+    pub rule_per_line: bool,
+}
+
+/// Prints the program back in canonical syntax: `in` first, the other workflows
+/// sorted by name, then the parts with every variable in declaration order
+pub fn format(input: &str, options: Options) -> Result<String, String> {
+    let compiled = compiler::compile(input)?;
+    Ok(program(
+        &compiled.functions,
+        &compiled.variables,
+        &compiled.pieces,
+        options,
+    ))
+}
+
+/// The workflows and, when there are any, a blank line and the parts.
+/// Without parts the variables are declared by the order the workflows use them in,
+/// so only programs with parts keep the variables' order when parsed again.
+pub(crate) fn program(
+    functions: &FunctionMap,
+    variables: &[String],
+    pieces: &[Piece],
+    options: Options,
+) -> String {
+    let mut output = workflows(functions, options);
+    if !pieces.is_empty() {
+        output.push_str("\n\n");
+        output.push_str(&parts(pieces, variables));
+    }
+    output
+}
+
+pub(crate) fn workflows(functions: &FunctionMap, options: Options) -> String {
+    let mut names: Vec<&String> = functions.keys().collect();
+    names.sort_by_key(|name| (*name != "in", *name));
+    names
+        .iter()
+        .map(|name| {
+            let rules: Vec<String> = functions[*name].iter().map(|s| s.to_string()).collect();
+            match options.rule_per_line {
+                true => format!("{}{{\n    {}\n}}", name, rules.join(",\n    ")),
+                false => format!("{}{{{}}}", name, rules.join(",")),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub(crate) fn parts(pieces: &[Piece], variables: &[String]) -> String {
+    pieces
+        .iter()
+        .map(|piece| {
+            let values: Vec<String> = variables
+                .iter()
+                .zip(piece)
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            format!("{{{}}}", values.join(","))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generator::{self, Random};
+
+    fn assert_round_trip(input: &str, options: Options) {
+        let compiled = compiler::compile(input).unwrap();
+        let printed = format(input, options).unwrap();
+        let parsed = compiler::compile(&printed).unwrap();
+        assert_eq!(parsed.functions, compiled.functions, "{}", printed);
+        assert_eq!(parsed.variables, compiled.variables);
+        assert_eq!(parsed.pieces, compiled.pieces);
+        assert_eq!(format(&printed, options).unwrap(), printed);
+    }
+
+    #[test]
+    fn test_format() {
+        let input = "qqz{s>2770:qs,m<1801:hdj,R}\nin{s<1351:px,qqz}\npx{a<2006:A,R}\nqs{A}\nhdj{m>=838:A,R}\n\n{x=787,m=2655,a=1222,s=2876}";
+        assert_eq!(
+            format(input, Options::default()).unwrap(),
+            "in{s<1351:px,qqz}\nhdj{m>=838:A,R}\npx{a<2006:A,R}\nqqz{s>2770:qs,m<1801:hdj,R}\nqs{A}\n\n{x=787,m=2655,a=1222,s=2876}"
+        );
+    }
+
+    #[test]
+    fn test_rule_per_line() {
+        let input = "in{s<1351:px,A}\npx{a<2006:A,R}\n\n{a=1,s=2}";
+        let options = Options {
+            rule_per_line: true,
+        };
+        assert_eq!(
+            format(input, options).unwrap(),
+            "in{\n    s<1351:px,\n    A\n}\npx{\n    a<2006:A,\n    R\n}\n\n{a=1,s=2}"
+        );
+    }
+
+    #[test]
+    fn test_missing_values_are_printed() {
+        let input = "in{x!=3:A,R}\n\n{x=1,m=2}\n{m=5}";
+        assert_eq!(
+            format(input, Options::default()).unwrap(),
+            "in{x!=3:A,R}\n\n{x=1,m=2}\n{x=0,m=5}"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = include_str!("./inputs/test.txt");
+        assert_round_trip(input, Options::default());
+        assert_round_trip(
+            input,
+            Options {
+                rule_per_line: true,
+            },
+        );
+    }
+
+    #[test]
+    fn test_round_trip_random_programs() {
+        let mut random = Random::new(0x36);
+        for i in 0..200 {
+            let input = generator::program(&mut random);
+            let options = Options {
+                rule_per_line: i % 2 == 0,
+            };
+            assert_round_trip(&input, options);
+        }
+    }
+}
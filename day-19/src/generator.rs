@@ -0,0 +1,95 @@
+// Random workflows and parts for the tests and the benchmark
+
+use crate::{
+    compiler::types::{BinaryComparison, Comparator, Result, Return, Statement, Variable},
+    formatter::{self, Options},
+    FunctionMap, Piece,
+};
+
+// xorshift, the tests need the same programs on every run
+pub struct Random(u64);
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random(seed.max(1))
+    }
+
+    pub fn next(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound
+    }
+}
+
+const VARIABLES: [&str; 4] = ["x", "m", "a", "s"];
+
+/// `count` workflows with up to 3 conditions each.
+/// Workflows only send parts to the ones after them, so there are no cycles.
+pub fn functions(random: &mut Random, count: usize) -> FunctionMap {
+    let name = |i: usize| match i {
+        0 => "in".to_string(),
+        i => format!("w{}", i),
+    };
+    let target = |random: &mut Random, from: usize| {
+        let choice = random.next((count - from) as u64 + 1) as usize;
+        match choice {
+            0 => Return::Final(Result::Accept),
+            1 => Return::Final(Result::Reject),
+            choice => Return::Function(name(from + choice - 1)),
+        }
+    };
+    let comparisons = [
+        BinaryComparison::LessThan,
+        BinaryComparison::GreaterThan,
+        BinaryComparison::Equal,
+        BinaryComparison::NotEqual,
+        BinaryComparison::LessOrEqual,
+        BinaryComparison::GreaterOrEqual,
+    ];
+
+    let mut functions = FunctionMap::new();
+    for i in 0..count {
+        let mut function = Vec::new();
+        for _ in 0..random.next(4) {
+            let index = random.next(4) as usize;
+            function.push(Statement::Compare(Comparator {
+                variable: Variable {
+                    index,
+                    name: VARIABLES[index].to_string(),
+                },
+                comparison: comparisons[random.next(6) as usize],
+                value: 1 + random.next(4000),
+                return_value: target(random, i),
+            }));
+        }
+        function.push(Statement::Return(target(random, i)));
+        functions.insert(name(i), function);
+    }
+    functions
+}
+
+/// `count` parts with values in `DOMAIN`
+pub fn pieces(random: &mut Random, count: usize) -> Vec<Piece> {
+    (0..count)
+        .map(|_| VARIABLES.iter().map(|_| 1 + random.next(4000)).collect())
+        .collect()
+}
+
+/// The workflows of `functions`, written in the input's syntax
+pub fn workflows(random: &mut Random, count: usize) -> String {
+    formatter::workflows(&functions(random, count), Options::default())
+}
+
+/// The parts of `pieces`, one per line
+pub fn parts(random: &mut Random, count: usize) -> String {
+    let variables: Vec<String> = VARIABLES.iter().map(|name| name.to_string()).collect();
+    formatter::parts(&pieces(random, count), &variables)
+}
+
+/// A program with up to 8 workflows and 20 parts
+pub fn program(random: &mut Random) -> String {
+    let count = 1 + random.next(8) as usize;
+    let workflows = workflows(random, count);
+    format!("{}\n\n{}", workflows, parts(random, 20))
+}
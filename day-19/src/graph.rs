@@ -0,0 +1,251 @@
+use crate::{
+    compiler::{
+        self,
+        types::{Comparator, Result, Statement},
+    },
+    interpreter::range::{self, Batch, Branch, Visitor},
+};
+
+/// Graphviz graph of the workflows: a node per workflow and an edge per rule,
+/// labelled with its condition like `a<2006`, the fallthrough edges are dashed.
+/// Fails with the rendered compiler errors.
+pub fn workflows_dot(input: &str) -> std::result::Result<String, String> {
+    let functions = compiler::compile(input)?.functions;
+    let mut names: Vec<&String> = functions.keys().collect();
+    names.sort_by_key(|name| (*name != "in", *name));
+
+    let mut lines = vec!["digraph workflows {".to_string()];
+    for name in &names {
+        lines.push(format!("    \"{}\" [shape=box];", name));
+    }
+    lines.push("    \"A\" [shape=doublecircle, color=green];".to_string());
+    lines.push("    \"R\" [shape=doublecircle, color=red];".to_string());
+    for name in &names {
+        for statement in &functions[*name] {
+            let edge = match statement {
+                Statement::Compare(comparator) => format!(
+                    "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                    name,
+                    comparator.return_value,
+                    condition(comparator)
+                ),
+                Statement::Return(return_value) => {
+                    format!("    \"{}\" -> \"{}\" [style=dashed];", name, return_value)
+                }
+            };
+            lines.push(edge);
+        }
+    }
+    lines.push("}".to_string());
+    Ok(lines.join("\n"))
+}
+
+// `a<2006:qkq` without the target
+fn condition(comparator: &Comparator) -> String {
+    format!(
+        "{}{}{}",
+        comparator.variable, comparator.comparison, comparator.value
+    )
+}
+
+/// Every rule tested as its own node, the parts go down a copy of a workflow
+/// for every rule sending them there. The branches no part takes are left out.
+enum Node {
+    Decision {
+        workflow: String,
+        condition: String,
+        matched: Option<Box<Node>>,
+        unmatched: Option<Box<Node>>,
+    },
+    // how many parts with values in `DOMAIN` end in the leaf
+    Leaf {
+        accepted: bool,
+        count: u128,
+    },
+}
+
+// Every batch that ends follows its decisions down the tree and adds its parts to the leaf
+struct TreeBuilder {
+    root: Option<Box<Node>>,
+}
+
+impl Visitor for TreeBuilder {
+    fn end(&mut self, batch: Batch, result: Result, path: &[Branch]) {
+        let mut slot = &mut self.root;
+        for branch in path {
+            let comparator = match branch.statement {
+                Statement::Compare(comparator) => comparator,
+                // the fallthrough has nothing to decide
+                Statement::Return(_) => continue,
+            };
+            let node = slot.get_or_insert_with(|| {
+                Box::new(Node::Decision {
+                    workflow: branch.workflow.to_string(),
+                    condition: condition(comparator),
+                    matched: None,
+                    unmatched: None,
+                })
+            });
+            slot = match node.as_mut() {
+                Node::Decision {
+                    matched, unmatched, ..
+                } => match branch.matched {
+                    true => matched,
+                    false => unmatched,
+                },
+                Node::Leaf { .. } => unreachable!("the same decisions end in the same leaf"),
+            };
+        }
+        let leaf = slot.get_or_insert_with(|| {
+            Box::new(Node::Leaf {
+                accepted: result == Result::Accept,
+                count: 0,
+            })
+        });
+        if let Node::Leaf { count, .. } = leaf.as_mut() {
+            *count += batch.size();
+        }
+    }
+}
+
+fn decision_tree(input: &str) -> std::result::Result<Node, String> {
+    let compiled = compiler::compile(input)?;
+    range::check_count(compiled.variables.len())?;
+    let mut builder = TreeBuilder { root: None };
+    range::walk(
+        &compiled.functions,
+        Batch::full(compiled.variables.len()),
+        &mut builder,
+    );
+    Ok(*builder.root.expect("every part ends somewhere"))
+}
+
+/// Graphviz graph of the flattened decision tree, the leaves show how many parts
+/// end in them, the same counts `interpreter::range` adds up.
+/// Fails with the rendered compiler errors, or when the parts are too many to count.
+pub fn decision_tree_dot(input: &str) -> std::result::Result<String, String> {
+    let mut lines = vec!["digraph decisions {".to_string()];
+    write_node(&decision_tree(input)?, &mut 0, &mut lines);
+    lines.push("}".to_string());
+    Ok(lines.join("\n"))
+}
+
+// Writes the node and its children, returns the node's id
+fn write_node(node: &Node, next_id: &mut usize, lines: &mut Vec<String>) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+    match node {
+        Node::Decision {
+            workflow,
+            condition,
+            matched,
+            unmatched,
+        } => {
+            lines.push(format!(
+                "    n{} [label=\"{}: {}\", shape=box];",
+                id, workflow, condition
+            ));
+            let children = [(matched, "yes"), (unmatched, "no")];
+            let mut edges = Vec::new();
+            for (child, label) in children {
+                if let Some(child) = child {
+                    let child = write_node(child, next_id, lines);
+                    edges.push(format!("    n{} -> n{} [label=\"{}\"];", id, child, label));
+                }
+            }
+            lines.extend(edges);
+        }
+        Node::Leaf { accepted, count } => {
+            let (result, color) = match accepted {
+                true => ("A", "green"),
+                false => ("R", "red"),
+            };
+            lines.push(format!(
+                "    n{} [label=\"{}\\n{}\", shape=ellipse, color={}];",
+                id, result, count, color
+            ));
+        }
+    }
+    id
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn leaves(node: &Node, accepted: &mut u128, rejected: &mut u128) {
+        match node {
+            Node::Decision {
+                matched, unmatched, ..
+            } => {
+                for child in [matched, unmatched].into_iter().flatten() {
+                    leaves(child, accepted, rejected);
+                }
+            }
+            Node::Leaf {
+                accepted: true,
+                count,
+            } => *accepted += count,
+            Node::Leaf { count, .. } => *rejected += count,
+        }
+    }
+
+    #[test]
+    fn test_workflows_dot() {
+        let dot =
+            workflows_dot("px{a<2006:qkq,m>2090:A,R}\nin{s<1351:px,R}\nqkq{A}\n\n{a=1,m=1,s=1}")
+                .unwrap();
+        assert_eq!(
+            dot,
+            "digraph workflows {\n    \
+             \"in\" [shape=box];\n    \
+             \"px\" [shape=box];\n    \
+             \"qkq\" [shape=box];\n    \
+             \"A\" [shape=doublecircle, color=green];\n    \
+             \"R\" [shape=doublecircle, color=red];\n    \
+             \"in\" -> \"px\" [label=\"s<1351\"];\n    \
+             \"in\" -> \"R\" [style=dashed];\n    \
+             \"px\" -> \"qkq\" [label=\"a<2006\"];\n    \
+             \"px\" -> \"A\" [label=\"m>2090\"];\n    \
+             \"px\" -> \"R\" [style=dashed];\n    \
+             \"qkq\" -> \"A\" [style=dashed];\n\
+             }"
+        );
+    }
+
+    #[test]
+    fn test_decision_tree_counts() {
+        let input = include_str!("./inputs/test.txt");
+        let (mut accepted, mut rejected) = (0, 0);
+        leaves(&decision_tree(input).unwrap(), &mut accepted, &mut rejected);
+        assert_eq!(accepted, 167_409_079_868_000);
+        assert_eq!(accepted + rejected, 4000_u128.pow(4));
+    }
+
+    #[test]
+    fn test_decision_tree_dot() {
+        let dot = decision_tree_dot("in{x<1001:a,R}\na{m>3000:A,R}\n\n{x=1,m=1}").unwrap();
+        assert_eq!(
+            dot,
+            "digraph decisions {\n    \
+             n0 [label=\"in: x<1001\", shape=box];\n    \
+             n1 [label=\"a: m>3000\", shape=box];\n    \
+             n2 [label=\"A\\n1000000\", shape=ellipse, color=green];\n    \
+             n3 [label=\"R\\n3000000\", shape=ellipse, color=red];\n    \
+             n1 -> n2 [label=\"yes\"];\n    \
+             n1 -> n3 [label=\"no\"];\n    \
+             n4 [label=\"R\\n12000000\", shape=ellipse, color=red];\n    \
+             n0 -> n1 [label=\"yes\"];\n    \
+             n0 -> n4 [label=\"no\"];\n\
+             }"
+        );
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let input = "in{x<1001:a,R}\n\n{x=1}";
+        for dot in [workflows_dot(input), decision_tree_dot(input)] {
+            assert!(dot.unwrap_err().contains("undefined workflow `a`"));
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use crate::{
     compiler::{
-        types::{BinaryComparison, Function, Result, Return, Statement},
+        types::{Function, Result, Return, Statement},
         Compiled,
     },
-    FunctionMap,
+    FunctionMap, Piece,
 };
 
 pub mod range;
@@ -12,22 +12,29 @@ pub fn evaluate(compile: Compiled) -> Vec<Result> {
     let mut results = Vec::new();
     let functions = compile.functions;
     let pieces = compile.pieces;
-    let main_function = functions.get("in").unwrap();
     for piece in pieces {
-        let result = evaluate_piece(piece, &functions, main_function);
+        let result = evaluate_piece(&piece, &functions, |_, _, _| {});
         results.push(result);
     }
-    return results;
+    results
 }
 
-fn evaluate_piece(piece: [u16; 4], functions: &FunctionMap, main_function: &Function) -> Result {
-    let variables = piece;
-    let mut current_function = main_function;
+/// Runs the part through the workflows from `in`,
+/// `on_rule` is told about every rule tested and whether the part matched it
+pub(crate) fn evaluate_piece(
+    piece: &Piece,
+    functions: &FunctionMap,
+    mut on_rule: impl FnMut(&str, usize, bool),
+) -> Result {
+    let mut current_function = "in";
     loop {
-        let evaluation = evaluate_function(&variables, current_function);
+        let function = functions.get(current_function).unwrap();
+        let evaluation = evaluate_function(piece, function, |rule, matched| {
+            on_rule(current_function, rule, matched)
+        });
         match evaluation {
             FunctionEvaluation::Function(function_name) => {
-                current_function = functions.get(&function_name).unwrap();
+                current_function = function_name;
             }
             FunctionEvaluation::Final(result) => {
                 return result;
@@ -36,44 +43,35 @@ fn evaluate_piece(piece: [u16; 4], functions: &FunctionMap, main_function: &Func
     }
 }
 
-enum FunctionEvaluation {
-    Function(String),
+enum FunctionEvaluation<'a> {
+    Function(&'a str),
     Final(Result),
 }
 
-fn evaluate_function(piece: &[u16; 4], function: &Function) -> FunctionEvaluation {
-    for statement in function {
-        match statement {
+fn evaluate_function<'a>(
+    piece: &Piece,
+    function: &'a Function,
+    mut on_rule: impl FnMut(usize, bool),
+) -> FunctionEvaluation<'a> {
+    for (rule, statement) in function.iter().enumerate() {
+        let return_value = match statement {
             Statement::Compare(comparator) => {
-                let variable = comparator.variable;
-                let comparison = comparator.comparison;
-                let value = comparator.value;
-                let return_value = comparator.return_value.clone();
-                let variable_value = piece[variable as usize];
-                let result = match comparison {
-                    BinaryComparison::GreaterThan => variable_value > value,
-                    BinaryComparison::LessThan => variable_value < value,
-                };
-                if result {
-                    match return_value {
-                        Return::Function(function_name) => {
-                            return FunctionEvaluation::Function(function_name);
-                        }
-                        Return::Final(result) => {
-                            return FunctionEvaluation::Final(result);
-                        }
-                    }
+                let matched = comparator.matches(piece);
+                on_rule(rule, matched);
+                if !matched {
+                    continue;
                 }
+                &comparator.return_value
             }
-            Statement::Return(result) => match result {
-                Return::Function(function_name) => {
-                    return FunctionEvaluation::Function(function_name.clone());
-                }
-                Return::Final(result) => {
-                    return FunctionEvaluation::Final(*result);
-                }
-            },
-        }
+            Statement::Return(return_value) => {
+                on_rule(rule, true);
+                return_value
+            }
+        };
+        return match return_value {
+            Return::Function(function_name) => FunctionEvaluation::Function(function_name),
+            Return::Final(result) => FunctionEvaluation::Final(*result),
+        };
     }
     panic!("No return statement found");
 }
@@ -85,7 +83,7 @@ mod test {
     #[test]
     fn test_evaluate() {
         let input = include_str!("../inputs/test.txt");
-        let compiled = crate::compiler::compile(input);
+        let compiled = crate::compiler::compile(input).unwrap();
         let results = evaluate(compiled);
         assert_eq!(
             results,
@@ -98,4 +96,28 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn test_evaluate_named_variables_and_wide_values() {
+        let input = "in{weight>=10000000000:R,color!=3:check,A}
+check{size<=2:A,color=7:A,R}
+
+{color=3,weight=10000000000}
+{color=3,weight=9999999999}
+{color=7,size=5}
+{color=1,size=2}
+{color=1,size=3}";
+        let compiled = crate::compiler::compile(input).unwrap();
+        let results = evaluate(compiled);
+        assert_eq!(
+            results,
+            vec![
+                Result::Reject,
+                Result::Accept,
+                Result::Accept,
+                Result::Accept,
+                Result::Reject
+            ]
+        );
+    }
 }
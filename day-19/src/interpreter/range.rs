@@ -1,190 +1,249 @@
 use crate::{
-    compiler::types::{BinaryComparison, Result, Return, Statement, Variable},
+    compiler::types::{Comparator, Result, Return, Statement, ValueRange},
     FunctionMap,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct PieceRange {
-    start: u64,
-    end: u64,
+/// Values every variable can take when counting the accepted parts
+pub const DOMAIN: ValueRange = ValueRange {
+    start: 1,
+    end: 4000,
+};
+
+/// Parts whose values fall in the ranges, one range per variable
+#[derive(Debug, Clone)]
+pub(crate) struct Batch {
+    pub ranges: Vec<ValueRange>,
 }
 
-impl PieceRange {
-    fn size(&self) -> u64 {
-        let result = self.end - self.start;
-        if result == 0 {
-            //returning 0 causes the batch entire size to be 0
-            return 1;
+impl Batch {
+    pub fn full(variables: usize) -> Self {
+        Batch {
+            ranges: vec![DOMAIN; variables],
         }
-        result
+    }
+
+    /// Every value a part can have, not only the ones counted by `full`
+    pub fn any(variables: usize) -> Self {
+        Batch {
+            ranges: vec![ValueRange::new(0, u64::MAX); variables],
+        }
+    }
+
+    /// Can only overflow when `check_count` fails for the number of variables
+    pub fn size(&self) -> u128 {
+        self.ranges.iter().map(|range| range.size()).product()
+    }
+
+    fn partial(&self, variable: usize, range: ValueRange) -> Self {
+        let mut ranges = self.ranges.clone();
+        ranges[variable] = range;
+        Batch { ranges }
+    }
+
+    /// Splits the batch into the pieces that match the comparator and the ones that fall through
+    pub fn split(&self, comparator: &Comparator) -> (Vec<Batch>, Vec<Batch>) {
+        let variable = comparator.variable.index;
+        let (matched, unmatched) = comparator.split(&self.ranges[variable]);
+        let partial = |ranges: Vec<ValueRange>| {
+            ranges
+                .into_iter()
+                .map(|range| self.partial(variable, range))
+                .collect()
+        };
+        (partial(matched), partial(unmatched))
     }
 }
 
-struct Batch {
-    x: PieceRange,
-    m: PieceRange,
-    a: PieceRange,
-    s: PieceRange,
+/// A rule a batch went through on its way from `in`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Branch<'a> {
+    pub workflow: &'a str,
+    pub rule: usize,
+    pub statement: &'a Statement,
+    // whether the batch matched the rule or fell through to the next one
+    pub matched: bool,
 }
 
-impl Batch {
-    fn size(&self) -> u64 {
-        self.x.size() * self.m.size() * self.a.size() * self.s.size()
-    }
-
-    fn partial(&self, variable: Variable, range: PieceRange) -> Self {
-        match variable {
-            Variable::X => Batch {
-                x: range,
-                m: self.m,
-                a: self.a,
-                s: self.s,
-            },
-            Variable::M => Batch {
-                x: self.x,
-                m: range,
-                a: self.a,
-                s: self.s,
-            },
-            Variable::A => Batch {
-                x: self.x,
-                m: self.m,
-                a: range,
-                s: self.s,
-            },
-            Variable::S => Batch {
-                x: self.x,
-                m: self.m,
-                a: self.a,
-                s: range,
-            },
-        }
+/// Told by `walk` how the batches are split and where they end
+pub(crate) trait Visitor {
+    /// The batch reached the rule, `matched` goes to its target and `unmatched` to the next rule
+    fn rule(
+        &mut self,
+        _workflow: &str,
+        _rule: usize,
+        _statement: &Statement,
+        _batch: &Batch,
+        _matched: &[Batch],
+        _unmatched: &[Batch],
+    ) {
     }
+
+    /// The batch ended in `result` after the rules of `path`
+    fn end(&mut self, _batch: Batch, _result: Result, _path: &[Branch]) {}
+
+    /// The batch went back to `workflow`, already in its `path`, its parts would go round forever
+    fn cycle(&mut self, _workflow: &str, _batch: &Batch, _path: &[Branch]) {}
 }
 
-pub fn evaluate(functions: FunctionMap) -> u64 {
-    let batch = Batch {
-        x: PieceRange {
-            start: 1,
-            end: 4001,
-        },
-        m: PieceRange {
-            start: 1,
-            end: 4001,
-        },
-        a: PieceRange {
-            start: 1,
-            end: 4001,
-        },
-        s: PieceRange {
-            start: 1,
-            end: 4001,
-        },
+/// Splits the batch by the rules of the workflows from `in` on, like the parts in it would go,
+/// and tells the visitor about every split.
+/// The batches sent to an undefined workflow or past the last rule are dropped,
+/// the compiler doesn't let either happen.
+pub(crate) fn walk(functions: &FunctionMap, batch: Batch, visitor: &mut impl Visitor) {
+    let mut walker = Walker {
+        functions,
+        visitor,
+        path: Vec::new(),
     };
+    walker.function(batch, "in");
+}
 
-    evaluate_function(batch, &functions, Return::Function("in".to_string()))
+struct Walker<'a, 'v, V> {
+    functions: &'a FunctionMap,
+    visitor: &'v mut V,
+    path: Vec<Branch<'a>>,
 }
 
-fn evaluate_function(batch: Batch, functions: &FunctionMap, last_return: Return) -> u64 {
-    let function = match last_return {
-        Return::Function(function_name) => functions.get(&function_name).unwrap(),
-        Return::Final(result) => match result {
-            Result::Accept => {
-                let size = batch.size();
-                return size;
-            }
-            Result::Reject => {
-                return 0;
-            }
-        },
-    };
-    let mut batch = batch;
-    let mut result = 0;
-
-    for statement in function {
-        match statement {
-            Statement::Compare(comparator) => {
-                let variable = comparator.variable;
-                let comparison = comparator.comparison;
-                let value = comparator.value;
-                let return_value = comparator.return_value.clone();
-                let start = match variable {
-                    Variable::X => batch.x.start,
-                    Variable::M => batch.m.start,
-                    Variable::A => batch.a.start,
-                    Variable::S => batch.s.start,
-                } as u16;
-                let end = match variable {
-                    Variable::X => batch.x.end,
-                    Variable::M => batch.m.end,
-                    Variable::A => batch.a.end,
-                    Variable::S => batch.s.end,
-                } as u16;
-                match comparison {
-                    BinaryComparison::GreaterThan => {
-                        if start > value {
-                            //Full range satisfies
-                            result += evaluate_function(batch, functions, return_value);
-                            return result;
-                        } else if end > value + 1 {
-                            let matched_batch = batch.partial(
-                                variable,
-                                PieceRange {
-                                    start: (value + 1) as u64,
-                                    end: end as u64,
-                                },
-                            );
-                            //Evaluate the matched batch
-                            result += evaluate_function(matched_batch, functions, return_value);
-                            //Update the batch to be the unmatched part
-                            batch = batch.partial(
-                                variable,
-                                PieceRange {
-                                    start: start as u64,
-                                    end: (value + 1) as u64,
-                                },
-                            );
-                        } else {
-                            //No match,"removing" range from the equation
-                            batch = batch.partial(variable, PieceRange { start: 1, end: 1 });
-                        }
-                    }
-                    BinaryComparison::LessThan => {
-                        if end <= value {
-                            //Full range satisfies
-                            result += evaluate_function(batch, functions, return_value);
-                            return result;
-                        } else if start < value {
-                            let matched_batch = batch.partial(
-                                variable,
-                                PieceRange {
-                                    start: start as u64,
-                                    end: value as u64,
-                                },
-                            );
-                            result += evaluate_function(matched_batch, functions, return_value);
-                            batch = batch.partial(
-                                variable,
-                                PieceRange {
-                                    start: value as u64,
-                                    end: end as u64,
-                                },
-                            );
-                        } else {
-                            //No match,"removing" range from the equation
-                            batch = batch.partial(variable, PieceRange { start: 1, end: 1 });
-                        }
-                    }
-                };
+impl<'a, V: Visitor> Walker<'a, '_, V> {
+    fn function(&mut self, batch: Batch, name: &str) {
+        let (name, function) = match self.functions.get_key_value(name) {
+            Some(function) => function,
+            None => return,
+        };
+        // the parts' values never change, a workflow seen twice is seen forever
+        if self.path.iter().any(|branch| branch.workflow == name) {
+            self.visitor.cycle(name, &batch, &self.path);
+            return;
+        }
+        self.statements(batch, name, 0, function);
+    }
+
+    fn statements(
+        &mut self,
+        batch: Batch,
+        workflow: &'a str,
+        rule: usize,
+        statements: &'a [Statement],
+    ) {
+        let (statement, rest) = match statements.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+        let (matched, unmatched) = match statement {
+            Statement::Compare(comparator) => batch.split(comparator),
+            //Fallthrough
+            Statement::Return(_) => (vec![batch.clone()], Vec::new()),
+        };
+        self.visitor
+            .rule(workflow, rule, statement, &batch, &matched, &unmatched);
+
+        let branch = |matched: bool| Branch {
+            workflow,
+            rule,
+            statement,
+            matched,
+        };
+        for matched in matched {
+            self.path.push(branch(true));
+            match statement.return_value() {
+                Return::Function(function_name) => self.function(matched, function_name),
+                Return::Final(result) => self.visitor.end(matched, *result, &self.path),
             }
-            Statement::Return(return_value) => match return_value {
-                _ => {
-                    //Fallthrough
-                    result += evaluate_function(batch, functions, return_value.clone());
-                    return result;
-                }
-            },
+            self.path.pop();
+        }
+        //The unmatched batches fall through to the next statements
+        for unmatched in unmatched {
+            self.path.push(branch(false));
+            self.statements(unmatched, workflow, rule + 1, rest);
+            self.path.pop();
         }
     }
-    panic!("No return statement");
+}
+
+// Adds up the sizes of the accepted batches
+struct Accepted(u128);
+
+impl Visitor for Accepted {
+    fn end(&mut self, batch: Batch, result: Result, _path: &[Branch]) {
+        if result == Result::Accept {
+            self.0 += batch.size();
+        }
+    }
+}
+
+/// Fails when the parts with `variables` values in `DOMAIN` are too many to count in a u128.
+/// The batches split from them are smaller, their sizes fit when this passes.
+pub fn check_count(variables: usize) -> std::result::Result<(), String> {
+    let count = (0..variables).try_fold(1_u128, |count, _| count.checked_mul(DOMAIN.size()));
+    match count {
+        Some(_) => Ok(()),
+        None => Err(format!(
+            "the parts of {} variables are too many to count, 4000^{} doesn't fit in 128 bits",
+            variables, variables
+        )),
+    }
+}
+
+/// How many parts with `variables` values in `DOMAIN` get accepted,
+/// fails when `check_count` does
+pub fn evaluate(functions: &FunctionMap, variables: usize) -> std::result::Result<u128, String> {
+    check_count(variables)?;
+    let mut accepted = Accepted(0);
+    walk(functions, Batch::full(variables), &mut accepted);
+    Ok(accepted.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::compiler::compile;
+
+    fn accepted(input: &str) -> u128 {
+        let compiled = compile(input).unwrap();
+        evaluate(&compiled.functions, compiled.variables.len()).unwrap()
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let input = include_str!("../inputs/test.txt");
+        assert_eq!(accepted(input), 167_409_079_868_000);
+    }
+
+    #[test]
+    fn test_rule_that_never_matches_keeps_the_batch() {
+        let input = "in{x>4000:R,m<1:R,A}\n\n{x=0,m=0,a=0,s=0}";
+        assert_eq!(accepted(input), 4000_u128.pow(4));
+    }
+
+    #[test]
+    fn test_equality() {
+        // everything but x=1 and a=3
+        assert_eq!(accepted("in{x=1:R,a!=3:A,R}\n\n{x=0,a=0}"), 3999 * 3999);
+        assert_eq!(accepted("in{x<=10:A,x>=3991:A,R}\n\n{x=0}"), 20);
+    }
+
+    #[test]
+    fn test_many_variables() {
+        // more variables than fit in a u64 count
+        let input = "in{a>2000:R,A}\n\n{a=0,b=0,c=0,d=0,e=0,f=0}";
+        assert_eq!(accepted(input), 2000 * 4000_u128.pow(5));
+    }
+
+    #[test]
+    fn test_too_many_variables_to_count() {
+        let names: Vec<String> = ('a'..='j').map(|name| name.to_string()).collect();
+        let rules: Vec<String> = names.iter().map(|name| format!("{}<0:R", name)).collect();
+        let input = format!("in{{{},A}}", rules.join(","));
+        assert_eq!(accepted(&input), 4000_u128.pow(10));
+
+        let input = format!("in{{{},k<0:R,A}}", rules.join(","));
+        let compiled = compile(&input).unwrap();
+        assert_eq!(
+            evaluate(&compiled.functions, compiled.variables.len()),
+            Err(
+                "the parts of 11 variables are too many to count, 4000^11 doesn't fit in 128 bits"
+                    .to_string()
+            )
+        );
+    }
 }
@@ -3,10 +3,20 @@ use std::collections::HashMap;
 use compiler::types::Function;
 
 type FunctionMap = HashMap<String, Function>;
-type Piece = [u16; 4];
+type Piece = Vec<u64>;
 
-mod compiler;
-mod interpreter;
+pub mod compiler;
+pub mod interpreter;
+
+pub mod debugger;
+pub mod diagnostics;
+pub mod formatter;
+pub mod generator;
+pub mod graph;
+pub mod optimizer;
+pub mod regions;
+pub mod repl;
+pub mod vm;
 
 pub mod part_1;
 pub mod part_2;
@@ -1,15 +1,4 @@
-use std::collections::HashMap;
-
-use compiler::types::Function;
-
-mod compiler;
-mod interpreter;
-
-mod part_1;
-mod part_2;
-
-type FunctionMap = HashMap<String, Function>;
-type Piece = [u16; 4];
+use day_19_lib::{part_1, part_2};
 
 fn main() {
     let input = include_str!("./inputs/input.txt");
@@ -0,0 +1,317 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    compiler::{
+        self,
+        types::{Return, Statement},
+    },
+    formatter::{self, Options},
+    interpreter::range::{self, Batch, Visitor},
+    FunctionMap,
+};
+
+/// Optimizes the workflows of the input and writes them back in the input's syntax,
+/// followed by the parts.
+/// The optimized workflows accept the same parts, whatever their values.
+pub fn optimize_input(input: &str) -> Result<String, String> {
+    let compiled = compiler::compile(input)?;
+    let functions = optimize(compiled.functions, compiled.variables.len());
+    Ok(formatter::program(
+        &functions,
+        &compiled.variables,
+        &compiled.pieces,
+        Options::default(),
+    ))
+}
+
+/// Runs every pass until none of them changes the workflows:
+/// - rules that can't match with the ranges that reach them are removed
+/// - rules sending to the same place as the fallthrough are folded into it
+/// - workflows that always end in the same place are replaced by that place
+/// - workflows used once, as a fallthrough, are inlined
+pub(crate) fn optimize(mut functions: FunctionMap, variables: usize) -> FunctionMap {
+    loop {
+        let mut changed = remove_impossible_rules(&mut functions, variables);
+        // before the other passes, which expect no part to go round the workflows left
+        changed |= remove_unreachable(&mut functions);
+        changed |= fold_fallthrough(&mut functions);
+        changed |= collapse_constant_workflows(&mut functions);
+        changed |= inline_single_use(&mut functions);
+        if !changed {
+            return functions;
+        }
+    }
+}
+
+fn target(statement: &Statement) -> &Return {
+    match statement {
+        Statement::Compare(comparator) => &comparator.return_value,
+        Statement::Return(return_value) => return_value,
+    }
+}
+
+fn target_mut(statement: &mut Statement) -> &mut Return {
+    match statement {
+        Statement::Compare(comparator) => &mut comparator.return_value,
+        Statement::Return(return_value) => return_value,
+    }
+}
+
+// Which rules of which workflows some parts match and some parts fall through
+#[derive(Default)]
+struct RuleUses {
+    matched: HashSet<(String, usize)>,
+    unmatched: HashSet<(String, usize)>,
+}
+
+impl Visitor for RuleUses {
+    fn rule(
+        &mut self,
+        workflow: &str,
+        rule: usize,
+        _statement: &Statement,
+        _batch: &Batch,
+        matched: &[Batch],
+        unmatched: &[Batch],
+    ) {
+        if !matched.is_empty() {
+            self.matched.insert((workflow.to_string(), rule));
+        }
+        if !unmatched.is_empty() {
+            self.unmatched.insert((workflow.to_string(), rule));
+        }
+    }
+}
+
+// Every part, whatever its values, is walked through the workflows:
+// a rule that none of them matches is removed and a rule that all of them match
+// becomes the fallthrough
+fn remove_impossible_rules(functions: &mut FunctionMap, variables: usize) -> bool {
+    let mut uses = RuleUses::default();
+    range::walk(functions, Batch::any(variables), &mut uses);
+
+    let mut changed = false;
+    for (name, function) in functions.iter_mut() {
+        let mut statements = Vec::new();
+        for (rule, statement) in function.iter().enumerate() {
+            let comparator = match statement {
+                Statement::Compare(comparator) => comparator,
+                Statement::Return(_) => {
+                    statements.push(statement.clone());
+                    break;
+                }
+            };
+            let used = |uses: &HashSet<(String, usize)>| uses.contains(&(name.clone(), rule));
+            if !used(&uses.matched) {
+                continue;
+            }
+            if !used(&uses.unmatched) {
+                statements.push(Statement::Return(comparator.return_value.clone()));
+                break;
+            }
+            statements.push(statement.clone());
+        }
+        if *function != statements {
+            *function = statements;
+            changed = true;
+        }
+    }
+    changed
+}
+
+// `x>10:A,A` is the same as `A`
+fn fold_fallthrough(functions: &mut FunctionMap) -> bool {
+    let mut changed = false;
+    for function in functions.values_mut() {
+        while function.len() >= 2 {
+            let fallthrough = target(&function[function.len() - 1]);
+            if target(&function[function.len() - 2]) != fallthrough {
+                break;
+            }
+            function.remove(function.len() - 2);
+            changed = true;
+        }
+    }
+    changed
+}
+
+// A workflow like `gd{R}` is replaced by `R` wherever it's used
+fn collapse_constant_workflows(functions: &mut FunctionMap) -> bool {
+    let mut constants: HashMap<String, Return> = functions
+        .iter()
+        .filter(|(name, _)| *name != "in")
+        .filter_map(|(name, function)| match &function[..] {
+            [Statement::Return(return_value)] => Some((name.clone(), return_value.clone())),
+            _ => None,
+        })
+        .collect();
+    if constants.is_empty() {
+        return false;
+    }
+    // `a{b}` and `b{R}` both become `R`
+    let names: Vec<String> = constants.keys().cloned().collect();
+    for name in names {
+        let mut constant = constants[&name].clone();
+        while let Some(next) = match &constant {
+            Return::Function(next) => constants.get(next),
+            Return::Final(_) => None,
+        } {
+            constant = next.clone();
+        }
+        constants.insert(name, constant);
+    }
+
+    for function in functions.values_mut() {
+        for statement in function.iter_mut() {
+            let return_value = target_mut(statement);
+            if let Return::Function(name) = return_value {
+                if let Some(constant) = constants.get(name) {
+                    *return_value = constant.clone();
+                }
+            }
+        }
+    }
+    for name in constants.keys() {
+        functions.remove(name);
+    }
+    true
+}
+
+// A workflow used only as the fallthrough of another one can take its place.
+// Workflows used by a condition are kept, the syntax has no way to join two conditions.
+fn inline_single_use(functions: &mut FunctionMap) -> bool {
+    let mut uses: HashMap<&str, usize> = HashMap::new();
+    for statement in functions.values().flatten() {
+        if let Return::Function(name) = target(statement) {
+            *uses.entry(name).or_default() += 1;
+        }
+    }
+
+    let inlined = functions
+        .iter()
+        .find_map(|(name, function)| match function.last() {
+            Some(Statement::Return(Return::Function(fallthrough)))
+                if fallthrough != "in"
+                    && fallthrough != name
+                    && uses[fallthrough.as_str()] == 1 =>
+            {
+                Some((name.clone(), fallthrough.clone()))
+            }
+            _ => None,
+        });
+    let (name, fallthrough) = match inlined {
+        Some(inlined) => inlined,
+        None => return false,
+    };
+    let statements = functions.remove(&fallthrough).unwrap();
+    let function = functions.get_mut(&name).unwrap();
+    function.pop();
+    function.extend(statements);
+    true
+}
+
+fn remove_unreachable(functions: &mut FunctionMap) -> bool {
+    let reached = reachable(functions);
+    let before = functions.len();
+    functions.retain(|name, _| reached.contains(name));
+    functions.len() != before
+}
+
+fn reachable(functions: &FunctionMap) -> HashSet<String> {
+    let mut reached = HashSet::from(["in".to_string()]);
+    let mut queue = VecDeque::from(["in"]);
+    while let Some(name) = queue.pop_front() {
+        for statement in &functions[name] {
+            if let Return::Function(next) = target(statement) {
+                if reached.insert(next.clone()) {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    reached
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        compiler::{compile, types::Result},
+        generator::{self, Random},
+        interpreter,
+    };
+
+    fn accepted(input: &str) -> (u128, Vec<Result>) {
+        let compiled = compile(input).unwrap();
+        let count = range::evaluate(&compiled.functions, compiled.variables.len()).unwrap();
+        (count, interpreter::evaluate(compiled))
+    }
+
+    #[test]
+    fn test_example_keeps_the_results() {
+        let input = include_str!("./inputs/test.txt");
+        let optimized = optimize_input(input).unwrap();
+        assert!(optimized.len() < input.len());
+        assert_eq!(accepted(&optimized), accepted(input));
+        assert_eq!(accepted(&optimized).0, 167_409_079_868_000);
+    }
+
+    #[test]
+    fn test_collapse_constant_workflows() {
+        let input = "in{s<1351:gd,A}\ngd{a>3333:R,R}\n\n{x=1,m=2,a=3,s=4}";
+        assert_eq!(
+            optimize_input(input).unwrap(),
+            "in{s<1351:R,A}\n\n{x=1,m=2,a=3,s=4}"
+        );
+    }
+
+    #[test]
+    fn test_fold_fallthrough() {
+        let input = "in{x>10:A,m<5:R,s>3:A,A}";
+        assert_eq!(optimize_input(input).unwrap(), "in{x>10:A,m<5:R,A}");
+    }
+
+    #[test]
+    fn test_inline_single_use() {
+        let input = "in{x>10:A,b}\nb{m<5:R,A}";
+        assert_eq!(optimize_input(input).unwrap(), "in{x>10:A,m<5:R,A}");
+        // used by a condition, it stays
+        let input = "in{x>10:b,A}\nb{m<5:R,A}";
+        assert_eq!(optimize_input(input).unwrap(), input);
+    }
+
+    #[test]
+    fn test_remove_impossible_rules() {
+        let input = "in{x>10:a,R}\na{x<5:R,m>3:A,R}";
+        assert_eq!(optimize_input(input).unwrap(), "in{x>10:a,R}\na{m>3:A,R}");
+        let input = "in{x>10:a,R}\na{x>5:A,m>3:R,A}";
+        assert_eq!(optimize_input(input).unwrap(), "in{x>10:A,R}");
+    }
+
+    #[test]
+    fn test_parts_outside_the_domain() {
+        // no part from 1 to 4000 matches `x>4000`, the part of the input does
+        let input = "in{x>4000:R,A}\n\n{x=5000}";
+        assert_eq!(optimize_input(input).unwrap(), input);
+        assert_eq!(accepted(input).1, vec![Result::Reject]);
+    }
+
+    #[test]
+    fn test_cycle_no_part_goes_round() {
+        let input = "in{x>10:a,R}\na{x<5:in,m>3:A,R}";
+        assert_eq!(optimize_input(input).unwrap(), "in{x>10:a,R}\na{m>3:A,R}");
+        // `a` and `b` only send parts to each other, but no part gets to them
+        let input = "in{x>10:c,R}\nc{x<5:a,A}\na{b}\nb{a}";
+        assert_eq!(optimize_input(input).unwrap(), "in{x>10:A,R}");
+    }
+
+    #[test]
+    fn test_random_programs_keep_the_results() {
+        let mut random = Random::new(0x2023_1219);
+        for _ in 0..500 {
+            let input = generator::program(&mut random);
+            let optimized = optimize_input(&input).unwrap();
+            assert_eq!(accepted(&optimized), accepted(&input), "{}", input);
+        }
+    }
+}
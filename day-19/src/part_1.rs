@@ -5,13 +5,13 @@ use crate::{
 
 pub fn process(input: &str) -> usize {
     let mut final_result = 0;
-    let compiled = compiler::compile(input);
+    let compiled = compiler::compile(input).unwrap_or_else(|errors| panic!("{}", errors));
     let pieces = compiled.pieces.clone();
     let results = interpreter::evaluate(compiled);
     for (result, piece) in results.iter().zip(pieces.iter()) {
         match result {
             Result::Accept => {
-                let piece_value = piece.iter().sum::<u16>() as usize;
+                let piece_value = piece.iter().sum::<u64>() as usize;
                 final_result += piece_value;
             }
             Result::Reject => {}
@@ -31,6 +31,13 @@ mod test {
         assert_eq!(result, 19114);
     }
 
+    #[test]
+    fn test_cycle_no_part_goes_round() {
+        // the parts in `a` are over 10, none of them goes back to `in`
+        let input = "in{x>10:a,R}\na{x<5:in,A}\n\n{x=20}";
+        assert_eq!(process(input), 20);
+    }
+
     #[test]
     fn test_input() {
         let input = include_str!("./inputs/input.txt");
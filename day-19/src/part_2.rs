@@ -1,10 +1,9 @@
 use crate::{compiler, interpreter};
 
-pub fn process(input: &str) -> u64 {
-    let functions = compiler::compile_only_functions(input);
-    let result = interpreter::range::evaluate(functions);
-
-    result
+pub fn process(input: &str) -> u128 {
+    let compiled = compiler::compile(input).unwrap_or_else(|errors| panic!("{}", errors));
+    interpreter::range::evaluate(&compiled.functions, compiled.variables.len())
+        .unwrap_or_else(|error| panic!("{}", error))
 }
 
 #[cfg(test)]
@@ -18,6 +17,12 @@ mod test {
         assert_eq!(result, 167_409_079_868_000);
     }
 
+    #[test]
+    #[should_panic(expected = "the parts of 11 variables are too many to count")]
+    fn test_too_many_variables() {
+        process("in{a<0:R,b<0:R,c<0:R,d<0:R,e<0:R,f<0:R,g<0:R,h<0:R,i<0:R,j<0:R,k<0:R,A}");
+    }
+
     #[test]
     fn test_input() {
         let input = include_str!("./inputs/input.txt");
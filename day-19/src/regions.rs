@@ -0,0 +1,278 @@
+use std::ops::RangeInclusive;
+
+use crate::{
+    compiler::{self, types::Result},
+    interpreter::range::{self, Batch, Branch, Visitor},
+};
+
+/// A rule a region went through
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub workflow: String,
+    pub rule: usize,
+    // the rule as written in the input
+    pub condition: String,
+    // whether the region matched the rule or fell through to the next one
+    pub matched: bool,
+}
+
+/// Parts whose values fall in the ranges all end in the same place by the same rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    // (start, end) of every variable, both ends included
+    pub ranges: Vec<(u64, u64)>,
+    pub path: Vec<Step>,
+    pub accepted: bool,
+}
+
+impl Region {
+    pub fn size(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|(start, end)| (end - start + 1) as u128)
+            .product()
+    }
+
+    pub fn contains(&self, piece: &[u64]) -> bool {
+        piece.len() == self.ranges.len()
+            && self
+                .ranges
+                .iter()
+                .zip(piece)
+                .all(|((start, end), value)| (start..=end).contains(&value))
+    }
+}
+
+/// Every part with values in `DOMAIN`, split in disjoint regions by the rules they go through
+pub struct Regions {
+    // names of the parts' values, in the order of the ranges
+    pub variables: Vec<String>,
+    regions: Vec<Region>,
+}
+
+impl Regions {
+    /// Fails with the rendered compiler errors, or when the parts are too many to count
+    pub fn new(input: &str) -> std::result::Result<Self, String> {
+        let compiled = compiler::compile(input)?;
+        range::check_count(compiled.variables.len())?;
+        let mut splitter = Splitter {
+            regions: Vec::new(),
+        };
+        range::walk(
+            &compiled.functions,
+            Batch::full(compiled.variables.len()),
+            &mut splitter,
+        );
+        Ok(Regions {
+            variables: compiled.variables,
+            regions: splitter.regions,
+        })
+    }
+
+    /// The regions whose parts get accepted
+    pub fn accepted(&self) -> impl Iterator<Item = &Region> {
+        self.regions.iter().filter(|region| region.accepted)
+    }
+
+    /// How many parts get accepted, the same as `interpreter::range::evaluate`
+    pub fn count(&self) -> u128 {
+        self.accepted().map(|region| region.size()).sum()
+    }
+
+    /// The region holding the part, `None` when a value is out of `DOMAIN`
+    pub fn find(&self, piece: &[u64]) -> Option<&Region> {
+        self.regions.iter().find(|region| region.contains(piece))
+    }
+
+    pub fn is_accepted(&self, piece: &[u64]) -> bool {
+        self.find(piece).is_some_and(|region| region.accepted)
+    }
+
+    /// The rules the part goes through, accepted or not
+    pub fn path(&self, piece: &[u64]) -> Option<&[Step]> {
+        self.find(piece).map(|region| &region.path[..])
+    }
+
+    /// How many accepted parts have `variable` in `range`, `None` for an unknown variable
+    pub fn count_where(&self, variable: &str, range: RangeInclusive<u64>) -> Option<u128> {
+        let index = self.variables.iter().position(|name| name == variable)?;
+        let count = self
+            .accepted()
+            .map(|region| {
+                let mut ranges = region.ranges.clone();
+                let (start, end) = ranges[index];
+                let start = start.max(*range.start());
+                let end = end.min(*range.end());
+                if start > end {
+                    return 0;
+                }
+                ranges[index] = (start, end);
+                Region {
+                    ranges,
+                    path: Vec::new(),
+                    accepted: true,
+                }
+                .size()
+            })
+            .sum();
+        Some(count)
+    }
+
+    /// The accepted regions as
+    /// `{"variables":["x",..],"regions":[{"ranges":[[1,1350],..],"path":[..]},..]}`
+    pub fn to_json(&self) -> String {
+        let variables: Vec<String> = self.variables.iter().map(|name| string(name)).collect();
+        let regions: Vec<String> = self
+            .accepted()
+            .map(|region| {
+                let ranges: Vec<String> = region
+                    .ranges
+                    .iter()
+                    .map(|(start, end)| format!("[{},{}]", start, end))
+                    .collect();
+                let path: Vec<String> = region
+                    .path
+                    .iter()
+                    .map(|step| {
+                        format!(
+                            "{{\"workflow\":{},\"rule\":{},\"condition\":{},\"matched\":{}}}",
+                            string(&step.workflow),
+                            step.rule,
+                            string(&step.condition),
+                            step.matched
+                        )
+                    })
+                    .collect();
+                format!(
+                    "{{\"ranges\":[{}],\"path\":[{}]}}",
+                    ranges.join(","),
+                    path.join(",")
+                )
+            })
+            .collect();
+        format!(
+            "{{\"variables\":[{}],\"regions\":[{}]}}",
+            variables.join(","),
+            regions.join(",")
+        )
+    }
+}
+
+// JSON string, the names only have letters and digits but the quotes are escaped anyway
+fn string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Every batch that ends is a region, the path it took is its rules
+struct Splitter {
+    regions: Vec<Region>,
+}
+
+impl Visitor for Splitter {
+    fn end(&mut self, batch: Batch, result: Result, path: &[Branch]) {
+        self.regions.push(Region {
+            ranges: batch
+                .ranges
+                .iter()
+                .map(|range| (range.start, range.end))
+                .collect(),
+            path: path
+                .iter()
+                .map(|branch| Step {
+                    workflow: branch.workflow.to_string(),
+                    rule: branch.rule,
+                    condition: branch.statement.to_string(),
+                    matched: branch.matched,
+                })
+                .collect(),
+            accepted: result == Result::Accept,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_count() {
+        let input = include_str!("./inputs/test.txt");
+        let regions = Regions::new(input).unwrap();
+        assert_eq!(regions.count(), 167_409_079_868_000);
+        let all: u128 = regions.regions.iter().map(|region| region.size()).sum();
+        assert_eq!(all, 4000_u128.pow(4));
+    }
+
+    #[test]
+    fn test_regions_are_disjoint() {
+        let input = include_str!("./inputs/test.txt");
+        let regions = Regions::new(input).unwrap().regions;
+        for (i, a) in regions.iter().enumerate() {
+            for b in &regions[i + 1..] {
+                let overlap = a
+                    .ranges
+                    .iter()
+                    .zip(&b.ranges)
+                    .all(|(a, b)| a.0 <= b.1 && b.0 <= a.1);
+                assert!(!overlap, "{:?} and {:?}", a.ranges, b.ranges);
+            }
+        }
+    }
+
+    #[test]
+    fn test_queries() {
+        let input = include_str!("./inputs/test.txt");
+        let regions = Regions::new(input).unwrap();
+        let pieces = compiler::compile(input).unwrap().pieces;
+        let accepted: Vec<bool> = pieces.iter().map(|p| regions.is_accepted(p)).collect();
+        assert_eq!(accepted, vec![true, false, true, false, true]);
+
+        let path: Vec<(&str, usize, bool)> = regions
+            .path(&pieces[0])
+            .unwrap()
+            .iter()
+            .map(|step| (step.workflow.as_str(), step.rule, step.matched))
+            .collect();
+        assert_eq!(
+            path,
+            vec![
+                ("in", 0, false),
+                ("in", 1, true),
+                ("qqz", 0, true),
+                ("qs", 0, false),
+                ("qs", 1, true),
+                ("lnx", 0, true),
+            ]
+        );
+        assert_eq!(regions.path(&[0, 1, 1, 1]), None);
+    }
+
+    #[test]
+    fn test_count_where() {
+        let input = include_str!("./inputs/test.txt");
+        let regions = Regions::new(input).unwrap();
+        let low = regions.count_where("x", 1..=2000).unwrap();
+        let high = regions.count_where("x", 2001..=4000).unwrap();
+        assert_eq!(low + high, regions.count());
+        assert_eq!(regions.count_where("x", 5000..=6000), Some(0));
+        assert_eq!(regions.count_where("q", 1..=4000), None);
+
+        let regions = Regions::new("in{x<10:A,R}\n\n{x=1}").unwrap();
+        assert_eq!(regions.count_where("x", 5..=20), Some(5));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let regions = Regions::new("in{x<10:A,y>5:a,R}\na{A}\n\n{x=1,y=2}").unwrap();
+        assert_eq!(
+            regions.to_json(),
+            "{\"variables\":[\"x\",\"y\"],\"regions\":[\
+             {\"ranges\":[[1,9],[1,4000]],\"path\":[\
+             {\"workflow\":\"in\",\"rule\":0,\"condition\":\"x<10:A\",\"matched\":true}]},\
+             {\"ranges\":[[10,4000],[6,4000]],\"path\":[\
+             {\"workflow\":\"in\",\"rule\":0,\"condition\":\"x<10:A\",\"matched\":false},\
+             {\"workflow\":\"in\",\"rule\":1,\"condition\":\"y>5:a\",\"matched\":true},\
+             {\"workflow\":\"a\",\"rule\":0,\"condition\":\"A\",\"matched\":true}]}]}"
+        );
+    }
+}
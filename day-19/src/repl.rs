@@ -0,0 +1,267 @@
+use crate::{
+    compiler::{
+        self,
+        diagnostic::{self, Diagnostic},
+        types,
+    },
+    formatter::{self, Options},
+    interpreter::{self, range},
+    FunctionMap, Piece,
+};
+
+pub const HELP: &str = "\
+eval {x=..,m=..,a=..,s=..}  the verdict for a part and the rules it went through
+count                       how many parts with values in 1..=4000 get accepted
+define name{...}            adds or replaces a workflow
+list                        every workflow
+help                        this message
+quit                        leaves";
+
+/// The workflows loaded from a file, changed by the `define` commands
+pub struct Session {
+    functions: FunctionMap,
+    // names of the parts' values, in the order of the parts of the loaded file,
+    // or in the order the workflows use them when it has no parts
+    variables: Vec<String>,
+    // whether the loaded file has parts, the workflows can't use other variables then
+    declared: bool,
+}
+
+impl Session {
+    /// Fails with the rendered errors when the file doesn't compile, the warnings are ignored
+    pub fn load(input: &str) -> Result<Self, String> {
+        let (compiled, diagnostics) = compiler::analyze(input);
+        check(&diagnostics, input)?;
+        Ok(Session {
+            functions: compiled.functions,
+            variables: compiled.variables,
+            declared: !compiled.pieces.is_empty(),
+        })
+    }
+
+    /// Runs a command line, the output or the error is ready to print
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let line = line.trim();
+        let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+        let argument = argument.trim();
+        match command {
+            "eval" => self.eval(argument),
+            "count" => range::evaluate(&self.functions, self.variables.len())
+                .map(|count| count.to_string()),
+            "define" => self.define(argument),
+            "list" => Ok(formatter::workflows(&self.functions, Options::default())),
+            "help" => Ok(HELP.to_string()),
+            "" => Ok(String::new()),
+            command => Err(format!("unknown command `{}`, try `help`", command)),
+        }
+    }
+
+    fn eval(&self, argument: &str) -> Result<String, String> {
+        let piece = self.parse_piece(argument)?;
+        let mut lines = Vec::new();
+        let result =
+            interpreter::evaluate_piece(&piece, &self.functions, |workflow, rule, matched| {
+                let rule = &self.functions[workflow][rule];
+                lines.push(match matched {
+                    true => format!("{}: {} -> {}", workflow, rule, rule.return_value()),
+                    false => format!("{}: {} (no match)", workflow, rule),
+                });
+            });
+        lines.push(match result {
+            types::Result::Accept => "accepted".to_string(),
+            types::Result::Reject => "rejected".to_string(),
+        });
+        Ok(lines.join("\n"))
+    }
+
+    // {x=787,m=2655,a=1222,s=2876}, every variable of the file needs a value
+    fn parse_piece(&self, argument: &str) -> Result<Piece, String> {
+        let values = argument
+            .strip_prefix('{')
+            .and_then(|values| values.strip_suffix('}'))
+            .ok_or("expected a part like {x=787,m=2655,a=1222,s=2876}")?;
+        let mut piece: Vec<Option<u64>> = vec![None; self.variables.len()];
+        for value in values.split(',').filter(|value| !value.trim().is_empty()) {
+            let (name, value) = value
+                .split_once('=')
+                .ok_or_else(|| format!("expected `name=value`, found `{}`", value))?;
+            let name = name.trim();
+            let index = self
+                .variables
+                .iter()
+                .position(|variable| variable == name)
+                .ok_or_else(|| format!("undefined variable `{}`", name))?;
+            let value = value
+                .trim()
+                .parse()
+                .map_err(|_| format!("`{}` is not a number", value.trim()))?;
+            piece[index] = Some(value);
+        }
+        piece
+            .iter()
+            .zip(&self.variables)
+            .map(|(value, name)| value.ok_or_else(|| format!("missing a value for `{}`", name)))
+            .collect()
+    }
+
+    // The new workflow is checked with every other one, so it can't point to an undefined
+    // workflow, use an undeclared variable or close a cycle some parts go round.
+    // Every workflow is compiled again, the variables' indices change when the loaded file
+    // has no parts to declare them.
+    fn define(&mut self, argument: &str) -> Result<String, String> {
+        let (name, _) = argument
+            .split_once('{')
+            .ok_or("expected a workflow like px{a<2006:qkq,m>2090:A,rfg}")?;
+        let name = name.trim();
+        let mut others = self.functions.clone();
+        others.remove(name);
+
+        let mut source = argument.to_string();
+        if !others.is_empty() {
+            source.push('\n');
+            source.push_str(&formatter::workflows(&others, Options::default()));
+        }
+        if self.declared {
+            // declares the variables the workflows can use
+            let declaration = vec![0; self.variables.len()];
+            source.push_str("\n\n");
+            source.push_str(&formatter::parts(&[declaration], &self.variables));
+        }
+
+        let (compiled, diagnostics) = compiler::analyze(&source);
+        check(&diagnostics, &source)?;
+        let replaced = self.functions.contains_key(name);
+        self.functions = compiled.functions;
+        self.variables = compiled.variables;
+        Ok(match replaced {
+            true => format!("replaced `{}`", name),
+            false => format!("defined `{}`", name),
+        })
+    }
+}
+
+fn check(diagnostics: &[Diagnostic], source: &str) -> Result<(), String> {
+    let errors: Vec<Diagnostic> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_error())
+        .cloned()
+        .collect();
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(diagnostic::render_all(&errors, source)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session() -> Session {
+        Session::load(include_str!("./inputs/test.txt")).unwrap()
+    }
+
+    #[test]
+    fn test_eval() {
+        let mut session = session();
+        let output = session
+            .execute("eval {x=787,m=2655,a=1222,s=2876}")
+            .unwrap();
+        assert_eq!(
+            output,
+            "in: s<1351:px (no match)\n\
+             in: qqz -> qqz\n\
+             qqz: s>2770:qs -> qs\n\
+             qs: s>3448:A (no match)\n\
+             qs: lnx -> lnx\n\
+             lnx: m>1548:A -> A\n\
+             accepted"
+        );
+        let output = session.execute("eval {x=1679,m=44,a=2067,s=496}").unwrap();
+        assert!(output.ends_with("rejected"));
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        let mut session = session();
+        assert_eq!(
+            session.execute("eval {x=1,m=2,a=3}"),
+            Err("missing a value for `s`".to_string())
+        );
+        assert_eq!(
+            session.execute("eval {x=1,m=2,a=3,s=4,q=5}"),
+            Err("undefined variable `q`".to_string())
+        );
+        assert_eq!(
+            session.execute("eval {x=a,m=2,a=3,s=4}"),
+            Err("`a` is not a number".to_string())
+        );
+        assert!(session.execute("eval x=1").is_err());
+    }
+
+    #[test]
+    fn test_count() {
+        let mut session = session();
+        assert_eq!(session.execute("count").unwrap(), "167409079868000");
+    }
+
+    #[test]
+    fn test_define() {
+        let mut session = session();
+        assert_eq!(session.execute("define in{A}").unwrap(), "replaced `in`");
+        assert_eq!(
+            session.execute("count").unwrap(),
+            4000_u64.pow(4).to_string()
+        );
+        assert_eq!(
+            session
+                .execute("define in{x>2000:half,R}")
+                .unwrap_err()
+                .lines()
+                .next(),
+            Some("error: undefined workflow `half`")
+        );
+        assert_eq!(session.execute("define half{A}").unwrap(), "defined `half`");
+        session.execute("define in{x>2000:half,R}").unwrap();
+        assert_eq!(
+            session.execute("count").unwrap(),
+            (2000 * 4000_u64.pow(3)).to_string()
+        );
+        assert!(session.execute("define half{x>1:in,A}").is_err());
+        // the parts in `half` are over 2000, none of them goes back to `in`
+        assert_eq!(
+            session.execute("define half{x<1000:in,A}").unwrap(),
+            "replaced `half`"
+        );
+        assert!(session.execute("define half{q>1:A,A}").is_err());
+    }
+
+    #[test]
+    fn test_define_without_parts() {
+        let mut session = Session::load("in{A}").unwrap();
+        session.execute("define in{x>1:A,R}").unwrap();
+        assert_eq!(session.execute("count").unwrap(), "3999");
+        assert_eq!(session.execute("define a{m<5:R,A}").unwrap(), "defined `a`");
+        session.execute("define in{x>1:a,R}").unwrap();
+        assert_eq!(session.execute("count").unwrap(), (3999 * 3996).to_string());
+        assert_eq!(
+            session.execute("eval {x=2,m=7}").unwrap(),
+            "in: x>1:a -> a\na: m<5:R (no match)\na: A -> A\naccepted"
+        );
+    }
+
+    #[test]
+    fn test_list() {
+        let mut session = Session::load("px{a<2006:A,R}\nin{s<1351:px,R}\n\n{a=1,s=2}").unwrap();
+        assert_eq!(
+            session.execute("list").unwrap(),
+            "in{s<1351:px,R}\npx{a<2006:A,R}"
+        );
+        assert!(session.execute("fly").is_err());
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let error = Session::load("in{x>10:px,R}").err().unwrap();
+        assert!(error.starts_with("error: undefined workflow `px`"));
+    }
+}
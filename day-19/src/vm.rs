@@ -0,0 +1,239 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use crate::{
+    compiler::{
+        self,
+        types::{BinaryComparison, Result, Return, Statement},
+    },
+    FunctionMap,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Opcode {
+    LessThan,
+    GreaterThan,
+    Equal,
+    NotEqual,
+    LessOrEqual,
+    GreaterOrEqual,
+    // the fallthrough, always jumps
+    Jump,
+}
+
+// Jump targets past the end of the code are the final results
+const ACCEPT: u32 = u32::MAX;
+const REJECT: u32 = u32::MAX - 1;
+
+/// Jumps to `target` when the value at `variable` passes the comparison with `value`,
+/// otherwise goes on to the next instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Instruction {
+    opcode: Opcode,
+    variable: u32,
+    value: u64,
+    target: u32,
+}
+
+/// The workflows laid one after the other, `in` first,
+/// with every jump pointing at the first instruction of its workflow
+pub struct Bytecode {
+    instructions: Vec<Instruction>,
+    variables: usize,
+}
+
+impl Bytecode {
+    /// Fails with the rendered compiler errors
+    pub fn compile(input: &str) -> std::result::Result<Self, String> {
+        let compiled = compiler::compile(input)?;
+        Ok(Bytecode::from_functions(
+            &compiled.functions,
+            compiled.variables.len(),
+        ))
+    }
+
+    pub(crate) fn from_functions(functions: &FunctionMap, variables: usize) -> Self {
+        let mut names: Vec<&String> = functions.keys().collect();
+        names.sort_by_key(|name| (*name != "in", *name));
+
+        let mut starts: HashMap<&str, u32> = HashMap::new();
+        let mut start = 0;
+        for name in &names {
+            starts.insert(name, start);
+            start += functions[*name].len() as u32;
+        }
+        let target = |return_value: &Return| match return_value {
+            Return::Function(name) => starts[name.as_str()],
+            Return::Final(Result::Accept) => ACCEPT,
+            Return::Final(Result::Reject) => REJECT,
+        };
+
+        let instructions = names
+            .iter()
+            .flat_map(|name| &functions[*name])
+            .map(|statement| match statement {
+                Statement::Compare(comparator) => Instruction {
+                    opcode: match comparator.comparison {
+                        BinaryComparison::LessThan => Opcode::LessThan,
+                        BinaryComparison::GreaterThan => Opcode::GreaterThan,
+                        BinaryComparison::Equal => Opcode::Equal,
+                        BinaryComparison::NotEqual => Opcode::NotEqual,
+                        BinaryComparison::LessOrEqual => Opcode::LessOrEqual,
+                        BinaryComparison::GreaterOrEqual => Opcode::GreaterOrEqual,
+                    },
+                    variable: comparator.variable.index as u32,
+                    value: comparator.value,
+                    target: target(&comparator.return_value),
+                },
+                Statement::Return(return_value) => Instruction {
+                    opcode: Opcode::Jump,
+                    variable: 0,
+                    value: 0,
+                    target: target(return_value),
+                },
+            })
+            .collect();
+        Bytecode {
+            instructions,
+            variables,
+        }
+    }
+
+    /// Whether the part, with one value per variable, is accepted
+    pub fn run(&self, piece: &[u64]) -> bool {
+        debug_assert_eq!(piece.len(), self.variables, "wrong number of values");
+        let mut pc = 0;
+        loop {
+            let instruction = &self.instructions[pc];
+            // a jump doesn't read a variable, a program without any has none to read
+            let value = || piece[instruction.variable as usize];
+            let jump = match instruction.opcode {
+                Opcode::LessThan => value() < instruction.value,
+                Opcode::GreaterThan => value() > instruction.value,
+                Opcode::Equal => value() == instruction.value,
+                Opcode::NotEqual => value() != instruction.value,
+                Opcode::LessOrEqual => value() <= instruction.value,
+                Opcode::GreaterOrEqual => value() >= instruction.value,
+                Opcode::Jump => true,
+            };
+            if !jump {
+                pc += 1;
+                continue;
+            }
+            match instruction.target {
+                ACCEPT => return true,
+                REJECT => return false,
+                target => pc = target as usize,
+            }
+        }
+    }
+}
+
+/// Part 1 on the VM, the sum of the values of the accepted parts
+pub fn process(input: &str) -> u64 {
+    let compiled = compiler::compile(input).unwrap_or_else(|errors| panic!("{}", errors));
+    let bytecode = Bytecode::from_functions(&compiled.functions, compiled.variables.len());
+    compiled
+        .pieces
+        .iter()
+        .filter(|piece| bytecode.run(piece))
+        .map(|piece| piece.iter().sum::<u64>())
+        .sum()
+}
+
+impl Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Opcode::LessThan => "lt",
+            Opcode::GreaterThan => "gt",
+            Opcode::Equal => "eq",
+            Opcode::NotEqual => "ne",
+            Opcode::LessOrEqual => "le",
+            Opcode::GreaterOrEqual => "ge",
+            Opcode::Jump => "jmp",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// One instruction per line, like `0003 gt 1 2090 -> A`
+impl Display for Bytecode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (pc, instruction) in self.instructions.iter().enumerate() {
+            let target = match instruction.target {
+                ACCEPT => "A".to_string(),
+                REJECT => "R".to_string(),
+                target => format!("{:04}", target),
+            };
+            match instruction.opcode {
+                Opcode::Jump => writeln!(f, "{:04} jmp -> {}", pc, target)?,
+                opcode => writeln!(
+                    f,
+                    "{:04} {} {} {} -> {}",
+                    pc, opcode, instruction.variable, instruction.value, target
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        generator::{self, Random},
+        interpreter,
+    };
+
+    fn results(input: &str) -> (Vec<bool>, Vec<bool>) {
+        let compiled = compiler::compile(input).unwrap();
+        let bytecode = Bytecode::from_functions(&compiled.functions, compiled.variables.len());
+        let vm = compiled.pieces.iter().map(|p| bytecode.run(p)).collect();
+        let tree = interpreter::evaluate(compiled)
+            .into_iter()
+            .map(|result| result == Result::Accept)
+            .collect();
+        (vm, tree)
+    }
+
+    #[test]
+    fn test_process() {
+        let input = include_str!("./inputs/test.txt");
+        assert_eq!(process(input), 19114);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let input = "px{a<2006:A,m>2090:qs,R}\nin{s>=5:px,A}\nqs{x!=1:A,R}\n\n{x=1,m=2,a=3,s=4}";
+        let bytecode = Bytecode::compile(input).unwrap();
+        assert_eq!(
+            bytecode.to_string(),
+            "0000 ge 3 5 -> 0002\n\
+             0001 jmp -> A\n\
+             0002 lt 2 2006 -> A\n\
+             0003 gt 1 2090 -> 0005\n\
+             0004 jmp -> R\n\
+             0005 ne 0 1 -> A\n\
+             0006 jmp -> R\n"
+        );
+    }
+
+    #[test]
+    fn test_program_without_variables() {
+        assert!(Bytecode::compile("in{A}").unwrap().run(&[]));
+        assert!(!Bytecode::compile("in{R}").unwrap().run(&[]));
+    }
+
+    #[test]
+    fn test_random_programs_match_the_interpreter() {
+        let mut random = Random::new(0x1219);
+        for _ in 0..500 {
+            let input = generator::program(&mut random);
+            let (vm, tree) = results(&input);
+            assert_eq!(vm, tree, "{}", input);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+// cargo bench -p day-20
+
+use std::time::Instant;
+
+use day_20_lib::machine::{generator, Machine};
+
+fn main() {
+    let input = generator::counters(&[3733, 3797, 3877, 4051]);
+    let mut machine = Machine::new(&input);
+    let presses = 1_000_000;
+    let start = Instant::now();
+    for _ in 0..presses {
+        machine.push_button();
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{} presses in {:?}, {:.0} presses per second, force {}",
+        presses,
+        elapsed,
+        presses as f64 / elapsed.as_secs_f64(),
+        machine.force()
+    );
+}
@@ -0,0 +1,186 @@
+use std::{collections::HashMap, ops::Range};
+
+use crate::machine::{bitset::BitSet, Machine};
+
+/// The memory of every module: the flip-flops by module id, the conjunctions'
+/// inputs by edge and the custom modules', numbered in input order, so equal
+/// states mean equal machines
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct State {
+    pub(crate) flip_flops: BitSet,
+    pub(crate) memory: BitSet,
+    // the custom modules' states one after the other
+    pub(crate) custom: Vec<bool>,
+}
+
+/// The presses after which the machine repeats its state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    // presses before the first repeated state
+    pub start: u32,
+    pub period: u32,
+    // low and high pulses sent after every press, the first entry before any press
+    counts: Vec<(u64, u64)>,
+}
+
+impl Cycle {
+    /// Low and high pulses sent by `presses` presses, counted from the machine that found the cycle
+    pub fn pulses_after(&self, presses: u64) -> (u64, u64) {
+        let (start, period) = (self.start as u64, self.period as u64);
+        if presses <= start + period {
+            return self.counts[presses as usize];
+        }
+        let (start_low, start_high) = self.counts[start as usize];
+        let (end_low, end_high) = self.counts[(start + period) as usize];
+        let cycles = (presses - start) / period;
+        let (rest_low, rest_high) = self.counts[(start + (presses - start) % period) as usize];
+        (
+            rest_low + cycles * (end_low - start_low),
+            rest_high + cycles * (end_high - start_high),
+        )
+    }
+
+    /// The product of the pulses sent by `presses` presses, like part 1 does for 1000
+    pub fn force_after(&self, presses: u64) -> u128 {
+        let (low, high) = self.pulses_after(presses);
+        low as u128 * high as u128
+    }
+}
+
+impl Machine {
+    pub fn state(&self) -> State {
+        State {
+            flip_flops: self.flip_flops.clone(),
+            memory: self.memory.clone(),
+            custom: self
+                .custom
+                .iter()
+                .flat_map(|module| module.state())
+                .collect(),
+        }
+    }
+
+    // Where the state of every custom module is in `State::custom`
+    pub(crate) fn custom_ranges(&self) -> Vec<Range<usize>> {
+        let mut start = 0;
+        self.custom
+            .iter()
+            .map(|module| {
+                let range = start..start + module.state().len();
+                start = range.end;
+                range
+            })
+            .collect()
+    }
+
+    /// Presses a copy of the machine until a state repeats, `None` if none does in `max_presses`
+    pub fn find_cycle(&self, max_presses: u32) -> Option<Cycle> {
+        let mut machine = self.clone();
+        let mut seen: HashMap<State, u32> = HashMap::from([(machine.state(), 0)]);
+        let mut counts = vec![(0, 0)];
+        let (low, high) = (machine.low_pulse_count, machine.high_pulse_count);
+        for press in 1..=max_presses {
+            machine.push_button();
+            counts.push((
+                machine.low_pulse_count - low,
+                machine.high_pulse_count - high,
+            ));
+            if let Some(start) = seen.insert(machine.state(), press) {
+                return Some(Cycle {
+                    start,
+                    period: press - start,
+                    counts,
+                });
+            }
+        }
+        None
+    }
+
+    /// Low and high pulses sent by `presses` more presses, without doing them when the
+    /// machine repeats a state in `max_presses`
+    pub fn pulses_after(&self, presses: u64, max_presses: u32) -> Option<(u64, u64)> {
+        if presses <= max_presses as u64 {
+            let mut machine = self.clone();
+            for _ in 0..presses {
+                machine.push_button();
+            }
+            return Some((
+                machine.low_pulse_count - self.low_pulse_count,
+                machine.high_pulse_count - self.high_pulse_count,
+            ));
+        }
+        Some(self.find_cycle(max_presses)?.pulses_after(presses))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FIRST: &str = "broadcaster -> a, b, c
+        %a -> b
+        %b -> c
+        %c -> inv
+        &inv -> a";
+
+    const SECOND: &str = "broadcaster -> a
+        %a -> inv, con
+        &inv -> b
+        %b -> con
+        &con -> output";
+
+    #[test]
+    fn test_find_cycle() {
+        let cycle = Machine::new(FIRST).find_cycle(100).unwrap();
+        assert_eq!((cycle.start, cycle.period), (0, 1));
+        let cycle = Machine::new(SECOND).find_cycle(100).unwrap();
+        assert_eq!((cycle.start, cycle.period), (0, 4));
+        assert_eq!(Machine::new(SECOND).find_cycle(3), None);
+    }
+
+    #[test]
+    fn test_pulses_after() {
+        // the puzzle's examples
+        let cycle = Machine::new(FIRST).find_cycle(100).unwrap();
+        assert_eq!(cycle.force_after(1000), 32_000_000);
+        let cycle = Machine::new(SECOND).find_cycle(100).unwrap();
+        assert_eq!(cycle.force_after(1000), 11_687_500);
+        assert_eq!(cycle.pulses_after(3), (13, 9));
+
+        let machine = Machine::new(SECOND);
+        for presses in 0..40 {
+            let mut pressed = machine.clone();
+            for _ in 0..presses {
+                pressed.push_button();
+            }
+            let counts = (pressed.low_pulse_count, pressed.high_pulse_count);
+            assert_eq!(cycle.pulses_after(presses), counts);
+            assert_eq!(machine.pulses_after(presses, 10), Some(counts));
+        }
+        assert_eq!(
+            cycle.pulses_after(1_000_000_000_000),
+            (4_250_000_000_000, 2_750_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_cycle_from_a_pressed_machine() {
+        let mut machine = Machine::new(SECOND);
+        machine.push_button();
+        let cycle = machine.find_cycle(100).unwrap();
+        assert_eq!((cycle.start, cycle.period), (0, 4));
+        machine.push_button();
+        machine.push_button();
+        let counts = (machine.low_pulse_count - 4, machine.high_pulse_count - 4);
+        assert_eq!(cycle.pulses_after(2), counts);
+    }
+
+    #[test]
+    fn test_no_cycle() {
+        let input = crate::machine::generator::counters(&[3733]);
+        let machine = Machine::new(&input);
+        assert_eq!(machine.find_cycle(1000), None);
+        assert_eq!(machine.pulses_after(1_000_000, 1000), None);
+        assert!(machine.pulses_after(1000, 1000).is_some());
+    }
+}
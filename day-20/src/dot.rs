@@ -0,0 +1,119 @@
+use crate::machine::{module::ModuleKind, Machine};
+
+impl Machine {
+    /// Graphviz graph of the machine in its current state. The shapes tell the
+    /// module kinds, the flip-flops and custom modules are green while they're on,
+    /// the conjunctions are green when every input is high and yellow when some are,
+    /// and the edges into conjunctions are red when they remember a high pulse
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec!["digraph machine {".to_string()];
+        for (id, label) in self.labels.iter().enumerate() {
+            let (shape, color) = match self.kinds[id] {
+                ModuleKind::Broadcaster => ("house", None),
+                ModuleKind::Output => ("doublecircle", None),
+                ModuleKind::FlipFlop => ("box", Some(on_color(self.flip_flops.get(id)))),
+                ModuleKind::Conjunction => {
+                    let color = match self.high_inputs[id] {
+                        0 => "white",
+                        high if high == self.inputs[id].len() => "green",
+                        _ => "yellow",
+                    };
+                    ("invtrapezium", Some(color))
+                }
+                ModuleKind::Custom(index) => {
+                    let module = &self.custom[index];
+                    let on = module.state().into_iter().any(|bit| bit);
+                    (module.shape(), Some(on_color(on)))
+                }
+            };
+            lines.push(match color {
+                Some(color) => format!(
+                    "    \"{}\" [shape={}, style=filled, fillcolor={}];",
+                    label, shape, color
+                ),
+                None => format!("    \"{}\" [shape={}];", label, shape),
+            });
+        }
+        for (from, outputs) in self.outputs.iter().enumerate() {
+            for output in outputs {
+                let edge = format!(
+                    "\"{}\" -> \"{}\"",
+                    self.labels[from], self.labels[output.to]
+                );
+                lines.push(match self.kinds[output.to] {
+                    ModuleKind::Conjunction => {
+                        let color = match self.memory.get(output.slot) {
+                            true => "red",
+                            false => "blue",
+                        };
+                        format!("    {} [color={}];", edge, color)
+                    }
+                    _ => format!("    {};", edge),
+                });
+            }
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+}
+
+fn on_color(on: bool) -> &'static str {
+    match on {
+        true => "green",
+        false => "white",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_to_dot() {
+        let input = "broadcaster -> a
+            %a -> inv, con
+            &inv -> b
+            %b -> con
+            &con -> output";
+        let mut machine = Machine::new(input);
+        machine.push_button();
+        assert_eq!(
+            machine.to_dot(),
+            "digraph machine {\n    \
+             \"broadcaster\" [shape=house];\n    \
+             \"a\" [shape=box, style=filled, fillcolor=green];\n    \
+             \"inv\" [shape=invtrapezium, style=filled, fillcolor=green];\n    \
+             \"b\" [shape=box, style=filled, fillcolor=green];\n    \
+             \"con\" [shape=invtrapezium, style=filled, fillcolor=green];\n    \
+             \"output\" [shape=doublecircle];\n    \
+             \"broadcaster\" -> \"a\";\n    \
+             \"a\" -> \"inv\" [color=red];\n    \
+             \"a\" -> \"con\" [color=red];\n    \
+             \"inv\" -> \"b\";\n    \
+             \"b\" -> \"con\" [color=red];\n    \
+             \"con\" -> \"output\";\n\
+             }"
+        );
+        machine.push_button();
+        let dot = machine.to_dot();
+        assert!(dot.contains("\"a\" [shape=box, style=filled, fillcolor=white];"));
+        assert!(dot.contains("\"con\" [shape=invtrapezium, style=filled, fillcolor=yellow];"));
+        assert!(dot.contains("\"a\" -> \"con\" [color=blue];"));
+    }
+
+    #[test]
+    fn test_custom_shapes() {
+        let mut registry = crate::machine::module::Registry::default();
+        registry
+            .register('#', || Box::new(crate::machine::modules::Divider::new(2)))
+            .unwrap();
+        let mut machine = Machine::with_registry("broadcaster -> c\n#c -> out", &registry);
+        assert!(machine
+            .to_dot()
+            .contains("\"c\" [shape=octagon, style=filled, fillcolor=white];"));
+        machine.push_button();
+        assert!(machine
+            .to_dot()
+            .contains("\"c\" [shape=octagon, style=filled, fillcolor=green];"));
+    }
+}
@@ -1,4 +1,7 @@
-mod machine;
+pub mod cycle;
+pub mod dot;
+pub mod machine;
+pub mod trace;
 
 pub mod part_1;
 pub mod part_2;
@@ -0,0 +1,421 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+};
+
+use super::{module::ModuleKind, Machine};
+
+// Presses to wait for every counter to send its first high pulse
+const MAX_PRESSES: u32 = 100_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnalysisError {
+    UnknownOutput(String),
+    NoInputs(String),
+    NotFedByConjunction {
+        output: String,
+        inputs: Vec<String>,
+    },
+    SharedModule {
+        hub: String,
+        first: String,
+        second: String,
+        module: String,
+    },
+    NoHighPulse {
+        input: String,
+        presses: u32,
+    },
+    NotACounter {
+        input: String,
+        press: u32,
+    },
+    NeverTogether {
+        first: String,
+        second: String,
+    },
+}
+
+impl Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::UnknownOutput(output) => write!(f, "no module named `{}`", output),
+            AnalysisError::NoInputs(output) => write!(f, "nothing sends pulses to `{}`", output),
+            AnalysisError::NotFedByConjunction { output, inputs } => write!(
+                f,
+                "`{}` should be fed by a single conjunction, found {}",
+                output,
+                inputs
+                    .iter()
+                    .map(|input| format!("`{}`", input))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            AnalysisError::SharedModule {
+                hub,
+                first,
+                second,
+                module,
+            } => write!(
+                f,
+                "the inputs `{}` and `{}` of `{}` share the module `{}`, they aren't independent counters",
+                first, second, hub, module
+            ),
+            AnalysisError::NoHighPulse { input, presses } => write!(
+                f,
+                "`{}` sent no high pulse in {} presses",
+                input, presses
+            ),
+            AnalysisError::NotACounter { input, press } => write!(
+                f,
+                "`{}` isn't a counter, its high pulse on press {} doesn't repeat the same way",
+                input, press
+            ),
+            AnalysisError::NeverTogether { first, second } => write!(
+                f,
+                "the counters `{}` and `{}` never send their high pulses on the same press",
+                first, second
+            ),
+        }
+    }
+}
+
+/// A subgraph started by the broadcaster that sends a high pulse every `period` presses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter {
+    // the module sending the high pulses to the hub
+    pub input: String,
+    // every module the input depends on, the broadcaster excluded
+    pub modules: Vec<String>,
+    // the press of the first high pulse, counted from the analyzed machine,
+    // the same as the period when the machine wasn't pressed yet
+    pub first: u32,
+    pub period: u32,
+}
+
+/// How the output gets its low pulse: the conjunction feeding it, the hub, sends it
+/// once every counter sent a high pulse on the same press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetAnalysis {
+    pub output: String,
+    pub hub: String,
+    pub counters: Vec<Counter>,
+}
+
+impl TargetAnalysis {
+    /// The first press where every counter sends its high pulse,
+    /// counted from the analyzed machine
+    pub fn first_low_pulse(&self) -> usize {
+        // every counter seen so far sends its high pulse on `press`, then every `step` presses
+        let (mut press, mut step) = (0, 1);
+        for counter in &self.counters {
+            let (first, period) = (counter.first as usize, counter.period as usize);
+            while press < first || (press - first) % period != 0 {
+                press += step;
+            }
+            step = lcm(step, period);
+        }
+        press
+    }
+}
+
+impl Machine {
+    fn inputs_of(&self, label: &str) -> Vec<String> {
+        let mut inputs: Vec<String> = self.inputs[self.ids[label]]
+            .iter()
+            .map(|(from, _)| self.labels[*from].clone())
+            .collect();
+        inputs.sort();
+        inputs.dedup();
+        inputs
+    }
+
+    // Every module that can send pulses to `label`, stopping at the broadcaster
+    fn dependencies(&self, label: &str) -> HashSet<String> {
+        let mut found = HashSet::from([label.to_string()]);
+        let mut stack = vec![label.to_string()];
+        while let Some(label) = stack.pop() {
+            for input in self.inputs_of(&label) {
+                let broadcaster = self.kinds[self.ids[&input]] == ModuleKind::Broadcaster;
+                if !broadcaster && found.insert(input.clone()) {
+                    stack.push(input);
+                }
+            }
+        }
+        found
+    }
+
+    // Whether the flip-flops are on and the conjunctions' memory, module by module
+    fn states(&self, modules: &[String]) -> Vec<bool> {
+        let mut states = Vec::new();
+        for label in modules {
+            let id = self.ids[label];
+            match self.kinds[id] {
+                ModuleKind::FlipFlop => states.push(self.flip_flops.get(id)),
+                ModuleKind::Conjunction => states.extend(
+                    self.inputs[id]
+                        .iter()
+                        .map(|(_, slot)| self.memory.get(*slot)),
+                ),
+                ModuleKind::Custom(index) => states.extend(self.custom[index].state()),
+                ModuleKind::Broadcaster | ModuleKind::Output => {}
+            }
+        }
+        states
+    }
+
+    /// Finds the conjunction feeding `output` and checks that its inputs are independent
+    /// counters, the only shape where the presses before a low pulse can be computed
+    pub fn analyze_target(&self, output: &str) -> Result<TargetAnalysis, AnalysisError> {
+        if !self.ids.contains_key(output) {
+            return Err(AnalysisError::UnknownOutput(output.to_string()));
+        }
+        let inputs = self.inputs_of(output);
+        let hub = match &inputs[..] {
+            [] => return Err(AnalysisError::NoInputs(output.to_string())),
+            [hub] if self.kinds[self.ids[hub]] == ModuleKind::Conjunction => hub.clone(),
+            _ => {
+                return Err(AnalysisError::NotFedByConjunction {
+                    output: output.to_string(),
+                    inputs,
+                })
+            }
+        };
+
+        let mut counters: Vec<Counter> = Vec::new();
+        for input in self.inputs_of(&hub) {
+            let modules = self.dependencies(&input);
+            for counter in &counters {
+                let shared = counter.modules.iter().find(|m| modules.contains(*m));
+                if let Some(module) = shared {
+                    return Err(AnalysisError::SharedModule {
+                        hub,
+                        first: counter.input.clone(),
+                        second: input,
+                        module: module.clone(),
+                    });
+                }
+            }
+            let mut modules: Vec<String> = modules.into_iter().collect();
+            modules.sort();
+            counters.push(Counter {
+                input,
+                modules,
+                first: 0,
+                period: 0,
+            });
+        }
+
+        self.find_periods(&mut counters)?;
+        check_in_phase(&counters)?;
+        Ok(TargetAnalysis {
+            output: output.to_string(),
+            hub,
+            counters,
+        })
+    }
+
+    // Presses a copy of the machine until every counter sent two high pulses.
+    // A counter is in the same state after both, so it repeats from then on.
+    // The presses are counted from the copy's first one, the machine may have been pressed.
+    fn find_periods(&self, counters: &mut [Counter]) -> Result<(), AnalysisError> {
+        let mut machine = self.clone();
+
+        // the press of the first high pulse and the counter's state after it
+        let mut first: HashMap<String, (u32, Vec<bool>)> = HashMap::new();
+        while counters.iter().any(|counter| counter.period == 0) {
+            let press = machine.press_count - self.press_count;
+            if press >= MAX_PRESSES {
+                let counter = counters.iter().find(|c| c.period == 0).unwrap();
+                return Err(match first.get(&counter.input) {
+                    Some((press, _)) => AnalysisError::NotACounter {
+                        input: counter.input.clone(),
+                        press: *press,
+                    },
+                    None => AnalysisError::NoHighPulse {
+                        input: counter.input.clone(),
+                        presses: MAX_PRESSES,
+                    },
+                });
+            }
+            machine.push_button();
+            let press = press + 1;
+            for counter in counters.iter_mut().filter(|counter| counter.period == 0) {
+                if machine.last_high[machine.ids[&counter.input]] != machine.press_count {
+                    continue;
+                }
+                let states = machine.states(&counter.modules);
+                let (first_press, first_states) = match first.get(&counter.input) {
+                    Some(first) => first,
+                    None => {
+                        first.insert(counter.input.clone(), (press, states));
+                        continue;
+                    }
+                };
+                if states != *first_states {
+                    return Err(AnalysisError::NotACounter {
+                        input: counter.input.clone(),
+                        press: *first_press,
+                    });
+                }
+                counter.first = *first_press;
+                counter.period = press - first_press;
+            }
+        }
+        Ok(())
+    }
+
+    /// Presses needed before `output` gets a low pulse, see `analyze_target`
+    pub fn first_low_pulse(&self, output: &str) -> Result<usize, AnalysisError> {
+        Ok(self.analyze_target(output)?.first_low_pulse())
+    }
+}
+
+// Two counters send their high pulses on the same press only when their first presses
+// are the same modulo the gcd of their periods
+fn check_in_phase(counters: &[Counter]) -> Result<(), AnalysisError> {
+    for (i, first) in counters.iter().enumerate() {
+        for second in &counters[i + 1..] {
+            let gcd = gcd(first.period as usize, second.period as usize);
+            if first.first as usize % gcd != second.first as usize % gcd {
+                return Err(AnalysisError::NeverTogether {
+                    first: first.input.clone(),
+                    second: second.input.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn lcm(first: usize, second: usize) -> usize {
+    first * second / gcd(first, second)
+}
+
+fn gcd(first: usize, second: usize) -> usize {
+    let mut max = first;
+    let mut min = second;
+    if min > max {
+        std::mem::swap(&mut max, &mut min);
+    }
+
+    loop {
+        let res = max % min;
+        if res == 0 {
+            return min;
+        }
+
+        max = min;
+        min = res;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // two counters, of 5 and 7 presses, joined by `hub`
+    const COUNTERS: &str = "broadcaster -> a0, b0
+        %a0 -> a1, ka
+        %a1 -> a2
+        %a2 -> ka
+        &ka -> a1, a0, ia
+        &ia -> hub
+        %b0 -> b1, kb
+        %b1 -> b2, kb
+        %b2 -> kb
+        &kb -> b0, ib
+        &ib -> hub
+        &hub -> rx";
+
+    #[test]
+    fn test_analyze_target() {
+        let machine = Machine::new(COUNTERS);
+        let analysis = machine.analyze_target("rx").unwrap();
+        assert_eq!(analysis.hub, "hub");
+        let periods: Vec<(&str, u32)> = analysis
+            .counters
+            .iter()
+            .map(|counter| (counter.input.as_str(), counter.period))
+            .collect();
+        assert_eq!(periods, vec![("ia", 5), ("ib", 7)]);
+        assert_eq!(
+            analysis.counters[0].modules,
+            vec!["a0", "a1", "a2", "ia", "ka"]
+        );
+        assert_eq!(analysis.first_low_pulse(), 35);
+        assert_eq!(machine.first_low_pulse("rx"), Ok(35));
+    }
+
+    #[test]
+    fn test_pressed_machine() {
+        let mut machine = Machine::new(COUNTERS);
+        for _ in 0..3 {
+            machine.push_button();
+        }
+        let analysis = machine.analyze_target("rx").unwrap();
+        let phases: Vec<(u32, u32)> = analysis
+            .counters
+            .iter()
+            .map(|counter| (counter.first, counter.period))
+            .collect();
+        assert_eq!(phases, vec![(2, 5), (4, 7)]);
+        assert_eq!(analysis.first_low_pulse(), 32);
+    }
+
+    #[test]
+    fn test_errors() {
+        let machine = Machine::new(COUNTERS);
+        assert_eq!(
+            machine.first_low_pulse("zz"),
+            Err(AnalysisError::UnknownOutput("zz".to_string()))
+        );
+        assert_eq!(
+            machine.first_low_pulse("broadcaster"),
+            Err(AnalysisError::NoInputs("broadcaster".to_string()))
+        );
+        let error = machine.first_low_pulse("a1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`a1` should be fed by a single conjunction, found `a0`, `ka`"
+        );
+
+        let shared = COUNTERS.replace("%b2 -> kb", "%b2 -> kb, a2");
+        let error = Machine::new(&shared).first_low_pulse("rx").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the inputs `ia` and `ib` of `hub` share the module `b0`, they aren't independent counters"
+        );
+    }
+
+    #[test]
+    fn test_not_a_counter() {
+        // `ia` sends high pulses on the first two presses, with `a` and `b` in other states
+        let input = "broadcaster -> a
+        %a -> b, ia
+        %b -> ia
+        &ia -> hub
+        &hub -> rx";
+        let error = Machine::new(input).first_low_pulse("rx").unwrap_err();
+        assert_eq!(
+            error,
+            AnalysisError::NotACounter {
+                input: "ia".to_string(),
+                press: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_generated_counters() {
+        let periods = [3733, 3797, 3877, 4051];
+        let machine = Machine::new(&crate::machine::generator::counters(&periods));
+        let analysis = machine.analyze_target("rx").unwrap();
+        let found: Vec<u32> = analysis.counters.iter().map(|c| c.period).collect();
+        assert_eq!(found, periods);
+        assert_eq!(
+            analysis.first_low_pulse(),
+            periods.iter().map(|p| *p as usize).product()
+        );
+    }
+}
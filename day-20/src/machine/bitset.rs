@@ -0,0 +1,50 @@
+/// Fixed size set of bits, packed in 64-bit words
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new(len: usize) -> BitSet {
+        BitSet {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        let bit = 1 << (index % 64);
+        if value {
+            self.words[index / 64] |= bit;
+        } else {
+            self.words[index / 64] &= !bit;
+        }
+    }
+
+    /// Flips the bit, returns its new value
+    pub fn toggle(&mut self, index: usize) -> bool {
+        self.words[index / 64] ^= 1 << (index % 64);
+        self.get(index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BitSet;
+
+    #[test]
+    fn test_bits() {
+        let mut bits = BitSet::new(130);
+        assert!(!bits.get(129));
+        bits.set(129, true);
+        bits.set(3, true);
+        assert!(bits.get(129) && bits.get(3));
+        assert!(!bits.toggle(3));
+        assert!(bits.toggle(64));
+        bits.set(129, false);
+        assert_eq!(bits.words, vec![0, 1, 0]);
+    }
+}
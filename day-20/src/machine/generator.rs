@@ -0,0 +1,37 @@
+// Machines shaped like the puzzle inputs: every counter is a chain of flip-flops
+// counting in binary, the conjunction `k` resets it once it reaches its period.
+// The high pulses of the counters meet in `hub`, which sends the low pulse to `rx`.
+
+const BITS: usize = 12;
+
+/// A counter per period, every period needs 12 bits with the highest one set
+pub fn counters(periods: &[u32]) -> String {
+    let mut lines = Vec::new();
+    let starts: Vec<String> = (0..periods.len()).map(|c| format!("c{}b0", c)).collect();
+    lines.push(format!("broadcaster -> {}", starts.join(", ")));
+    for (c, period) in periods.iter().enumerate() {
+        assert!((1 << (BITS - 1)..1 << BITS).contains(period));
+        let set = |bit: usize| period & (1 << bit) != 0;
+        for bit in 0..BITS {
+            let mut outputs = Vec::new();
+            if bit + 1 < BITS {
+                outputs.push(format!("c{}b{}", c, bit + 1));
+            }
+            if set(bit) {
+                outputs.push(format!("c{}k", c));
+            }
+            lines.push(format!("%c{}b{} -> {}", c, bit, outputs.join(", ")));
+        }
+        let mut resets = vec![format!("c{}b0", c)];
+        resets.extend(
+            (1..BITS)
+                .filter(|bit| !set(*bit))
+                .map(|bit| format!("c{}b{}", c, bit)),
+        );
+        resets.push(format!("c{}i", c));
+        lines.push(format!("&c{}k -> {}", c, resets.join(", ")));
+        lines.push(format!("&c{}i -> hub", c));
+    }
+    lines.push("&hub -> rx".to_string());
+    lines.join("\n")
+}
@@ -1,189 +1,214 @@
-use std::{cell::RefCell, collections::HashMap};
+use std::collections::{HashMap, VecDeque};
 
-use self::vertex::{Pulse, Vertex};
+use self::{
+    bitset::BitSet,
+    module::{Declared, Module, ModuleKind, Output, Pulse, Registry},
+};
 
-mod conjunction;
-mod flip_flop;
-mod vertex;
+mod analysis;
+pub mod bitset;
+pub mod generator;
+pub mod module;
+pub mod modules;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Edge {
-    from_id: u32,
-    pub from: String,
-    pub to: String,
-    to_id: u32,
-}
+pub use analysis::{AnalysisError, Counter, TargetAnalysis};
 
+/// The modules are numbered in the order they're found in the input, every edge
+/// numbers a slot in `memory`, where the receiving conjunction remembers its last pulse
 #[derive(Debug, Clone)]
 pub struct Machine {
-    vertices: HashMap<String, RefCell<Vertex>>,
-    pub edges: Vec<Edge>,
-    low_pulse_count: u32,
-    high_pulse_count: u32,
-    pub on: bool,
-    press_count: u32,
-    high_watch: HashMap<String, Option<u32>>,
+    pub(crate) labels: Vec<String>,
+    pub(crate) ids: HashMap<String, usize>,
+    pub(crate) kinds: Vec<ModuleKind>,
+    pub(crate) outputs: Vec<Vec<Output>>,
+    // the sender and the slot of every input
+    pub(crate) inputs: Vec<Vec<(usize, usize)>>,
+    pub(crate) broadcaster: Option<usize>,
+    // by module, whether the flip-flop is on
+    pub(crate) flip_flops: BitSet,
+    // by slot, whether the last pulse through the edge was high
+    pub(crate) memory: BitSet,
+    // by module, how many of the conjunction's slots are high
+    pub(crate) high_inputs: Vec<usize>,
+    // reused by every press, empty between presses
+    queue: VecDeque<(usize, Pulse)>,
+    pub(crate) low_pulse_count: u64,
+    pub(crate) high_pulse_count: u64,
+    pub(crate) press_count: u32,
+    // by module, the last press it sent a high pulse on, 0 if it never did
+    pub(crate) last_high: Vec<u32>,
+    // the modules declared with a registered prefix
+    pub(crate) custom: Vec<Box<dyn Module>>,
 }
 
 impl PartialEq for Machine {
     fn eq(&self, other: &Machine) -> bool {
-        self.vertices == other.vertices && self.edges == other.edges
+        self.labels == other.labels
+            && self.outputs == other.outputs
+            && self.flip_flops == other.flip_flops
+            && self.memory == other.memory
+            && self
+                .custom
+                .iter()
+                .map(|module| module.state())
+                .eq(other.custom.iter().map(|module| module.state()))
     }
 }
 
 impl Machine {
     pub fn new(input: &str) -> Machine {
-        let mut vertices = HashMap::new();
-        for line in input.lines() {
-            let line = line.trim();
-            let vertex = Vertex::new(line);
-            let label = vertex.label.clone();
-            let vertex = RefCell::new(vertex);
-            vertices.insert(label, vertex);
-        }
-        let mut graph = Machine {
-            vertices,
-            edges: Vec::new(),
+        Machine::with_registry(input, &Registry::default())
+    }
+
+    /// Builds the machine with the module types of the registry too
+    pub fn with_registry(input: &str, registry: &Registry) -> Machine {
+        let lines: Vec<&str> = input
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        let mut machine = Machine {
+            labels: Vec::new(),
+            ids: HashMap::new(),
+            kinds: Vec::new(),
+            outputs: Vec::new(),
+            inputs: Vec::new(),
+            broadcaster: None,
+            flip_flops: BitSet::new(0),
+            memory: BitSet::new(0),
+            high_inputs: Vec::new(),
+            queue: VecDeque::new(),
             low_pulse_count: 0,
             high_pulse_count: 0,
             press_count: 0,
-            on: false,
-            high_watch: HashMap::new(),
+            last_high: Vec::new(),
+            custom: Vec::new(),
         };
-        for line in input.lines() {
-            let line = line.trim();
-            let from = line.split_whitespace().next().unwrap().trim();
-            let from = Vertex::create_label(from);
+        for line in &lines {
+            let (declared, label) = registry.declare(line);
+            let id = machine.add_module(label);
+            let kind = match declared {
+                Declared::Builtin(kind) => kind,
+                Declared::Custom(module) => {
+                    machine.custom.push(module);
+                    ModuleKind::Custom(machine.custom.len() - 1)
+                }
+            };
+            machine.kinds[id] = kind;
+            if kind == ModuleKind::Broadcaster {
+                machine.broadcaster = Some(id);
+            }
+        }
+        let mut slots = 0;
+        for line in &lines {
+            let from = machine.ids[registry.label(line)];
             let connections = line.split_at(line.find("->").unwrap() + 2).1.trim();
             for connection in connections.split(',') {
-                let to = connection.trim().to_string();
-                graph.add_edge(from.clone(), to);
+                let to = machine.add_module(connection.trim());
+                let input = machine.inputs[to].len();
+                machine.outputs[from].push(Output {
+                    to,
+                    slot: slots,
+                    input,
+                });
+                machine.inputs[to].push((from, slots));
+                slots += 1;
             }
         }
-        graph
-    }
-
-    pub fn add_high_watch(&mut self, label: &str) {
-        self.high_watch.insert(label.to_string(), None);
-    }
-
-    pub fn high_watch_completed(&self) -> bool {
-        for (_, value) in self.high_watch.iter() {
-            if value.is_none() {
-                return false;
+        machine.flip_flops = BitSet::new(machine.labels.len());
+        machine.memory = BitSet::new(slots);
+        machine.high_inputs = vec![0; machine.labels.len()];
+        machine.last_high = vec![0; machine.labels.len()];
+        for (id, kind) in machine.kinds.iter().enumerate() {
+            if let ModuleKind::Custom(index) = kind {
+                machine.custom[*index].connect(machine.inputs[id].len());
             }
         }
-        true
+        machine
     }
 
-    pub fn highs(&self) -> Vec<usize> {
-        let mut highs = Vec::new();
-        for (_, value) in self.high_watch.iter() {
-            highs.push(value.unwrap() as usize);
+    // The id of the module, added as an output if it's new
+    fn add_module(&mut self, label: &str) -> usize {
+        if let Some(id) = self.ids.get(label) {
+            return *id;
         }
-        highs
+        let id = self.labels.len();
+        self.labels.push(label.to_string());
+        self.ids.insert(label.to_string(), id);
+        self.kinds.push(ModuleKind::Output);
+        self.outputs.push(Vec::new());
+        self.inputs.push(Vec::new());
+        id
     }
 
     pub fn push_button(&mut self) {
+        self.press(|_, _, _| {});
+    }
+
+    // Presses the button, `record` gets every pulse after the button's one,
+    // as the sender, the pulse and the receiver, in the order they're sent
+    pub(crate) fn press(&mut self, mut record: impl FnMut(usize, Pulse, usize)) {
         self.press_count += 1;
-        let mut queue = Vec::new();
-        let broadcaster_outs = self.get_output_edges("broadcaster");
-        let broadcaster_outs_len = broadcaster_outs.len() as u32;
-        for edge in broadcaster_outs {
-            let vertex = self.vertices.get(&edge.to).unwrap();
-            let mut vertex = vertex.borrow_mut();
-            let pulse = vertex.pulse(vertex::Pulse::Low, 0);
-            if pulse.is_some() {
-                queue.push((vertex.label.clone(), pulse.unwrap()));
+        // the button's pulse
+        self.low_pulse_count += 1;
+        let broadcaster = match self.broadcaster {
+            Some(broadcaster) => broadcaster,
+            None => return,
+        };
+        self.queue.push_back((broadcaster, Pulse::Low));
+        while let Some((from, pulse)) = self.queue.pop_front() {
+            let outputs = self.outputs[from].len();
+            match pulse {
+                Pulse::Low => self.low_pulse_count += outputs as u64,
+                Pulse::High => self.high_pulse_count += outputs as u64,
             }
-        }
-        self.low_pulse_count += broadcaster_outs_len + 1;
-
-        let mut next_queue = queue;
-        loop {
-            let mut temp_queue = Vec::new();
-            for (from, pulse) in next_queue.into_iter() {
-                let output_edges = self.get_output_edges(&from);
-                let mut output_edges_len = output_edges.len() as u32;
-                if output_edges.is_empty() {
-                    output_edges_len = 1;
-                }
-                for edge in output_edges {
-                    let vertex = self.vertices.get(&edge.to).unwrap();
-                    let mut vertex = vertex.borrow_mut();
-
-                    let pulse = vertex.pulse(pulse, edge.to_id as usize);
-                    let is_in_high_watch = self.high_watch.contains_key(&vertex.label);
-                    let vertex_label = vertex.label.clone();
-                    drop(vertex);
-                    if is_in_high_watch && pulse == Some(Pulse::High) {
-                        let value = self.high_watch.get_mut(&vertex_label).unwrap();
-                        *value = Some(self.press_count);
-                    }
-                    if pulse.is_some() {
-                        temp_queue.push((vertex_label, pulse.unwrap()));
+            for index in 0..outputs {
+                let Output { to, slot, input } = self.outputs[from][index];
+                record(from, pulse, to);
+                let sent = match self.kinds[to] {
+                    ModuleKind::FlipFlop => match pulse {
+                        Pulse::High => continue,
+                        Pulse::Low => match self.flip_flops.toggle(to) {
+                            true => Pulse::High,
+                            false => Pulse::Low,
+                        },
+                    },
+                    ModuleKind::Conjunction => {
+                        self.remember(to, slot, pulse);
+                        match self.high_inputs[to] == self.inputs[to].len() {
+                            true => Pulse::Low,
+                            false => Pulse::High,
+                        }
                     }
+                    ModuleKind::Broadcaster => pulse,
+                    ModuleKind::Output => continue,
+                    ModuleKind::Custom(module) => match self.custom[module].receive(input, pulse) {
+                        Some(pulse) => pulse,
+                        None => continue,
+                    },
+                };
+                if sent == Pulse::High {
+                    self.last_high[to] = self.press_count;
                 }
-                if pulse == vertex::Pulse::Low {
-                    self.low_pulse_count += output_edges_len;
-                } else {
-                    self.high_pulse_count += output_edges_len;
-                }
+                self.queue.push_back((to, sent));
             }
-            if temp_queue.is_empty() {
-                break;
-            }
-            next_queue = temp_queue;
         }
     }
 
-    pub fn force(&self) -> u64 {
-        self.high_pulse_count as u64 * self.low_pulse_count as u64
-    }
-
-    fn get_output_edges(&self, from: &str) -> Vec<Edge> {
-        let mut output_edges = Vec::new();
-        for edge in self.edges.iter() {
-            if edge.from == from {
-                output_edges.push(edge.clone());
-            }
+    fn remember(&mut self, conjunction: usize, slot: usize, pulse: Pulse) {
+        let high = pulse == Pulse::High;
+        if self.memory.get(slot) == high {
+            return;
+        }
+        self.memory.set(slot, high);
+        match high {
+            true => self.high_inputs[conjunction] += 1,
+            false => self.high_inputs[conjunction] -= 1,
         }
-        output_edges.sort_by(|a, b| a.from_id.cmp(&b.from_id));
-        output_edges
     }
 
-    fn add_edge(&mut self, from: String, to: String) {
-        let from_vertex = self.vertices.get(&from).unwrap();
-        let mut from_vertex = from_vertex.borrow_mut();
-        from_vertex.outputs_count += 1;
-        let from_id = from_vertex.outputs_count;
-        drop(from_vertex);
-        let result = self.vertices.get(&to);
-        let to_vertex = match result {
-            Some(vertex) => vertex,
-            None => {
-                let vertex = Vertex {
-                    inputs_count: 0,
-                    label: to.clone(),
-                    outputs_count: 0,
-                    vertex_type: vertex::VertexType::Dummy,
-                    last_pulse: None,
-                };
-                let vertex = RefCell::new(vertex);
-                self.vertices.insert(to.clone(), vertex);
-                self.vertices.get(&to).unwrap()
-            }
-        };
-        let mut to_vertex = to_vertex.borrow_mut();
-
-        let to_id = to_vertex.plus_input();
-        let edge = Edge {
-            from_id,
-            from,
-            to,
-            to_id,
-        };
-        self.edges.push(edge);
+    pub fn force(&self) -> u64 {
+        self.high_pulse_count * self.low_pulse_count
     }
 }
 
@@ -197,8 +222,9 @@ mod test {
       %c -> inv
       &inv -> a";
         let graph = super::Machine::new(input);
-        assert_eq!(graph.vertices.len(), 5);
-        assert_eq!(graph.edges.len(), 7);
+        assert_eq!(graph.labels.len(), 5);
+        assert_eq!(graph.outputs.iter().map(Vec::len).sum::<usize>(), 7);
+        assert_eq!(graph.inputs[graph.ids["a"]], vec![(0, 0), (4, 6)]);
     }
 
     #[test]
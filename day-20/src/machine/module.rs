@@ -0,0 +1,125 @@
+use std::fmt::{self, Debug, Display};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pulse {
+    High,
+    Low,
+}
+
+impl Display for Pulse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pulse::High => write!(f, "high"),
+            Pulse::Low => write!(f, "low"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleKind {
+    FlipFlop,
+    Conjunction,
+    Broadcaster,
+    // only receives pulses, like `rx`
+    Output,
+    // a module from the registry, by its index in the machine's custom modules
+    Custom(usize),
+}
+
+/// A module type added to the input syntax through a `Registry`
+pub trait Module: Debug {
+    /// Called once the machine is built, with the number of inputs of the module
+    fn connect(&mut self, _inputs: usize) {}
+
+    /// The pulse sent after receiving `pulse` through the input numbered `input`,
+    /// in the order the senders are declared, `None` sends nothing
+    fn receive(&mut self, input: usize, pulse: Pulse) -> Option<Pulse>;
+
+    /// The memory of the module, always as many bits, and the same bits have to
+    /// mean the same state
+    fn state(&self) -> Vec<bool> {
+        Vec::new()
+    }
+
+    /// Puts back a memory returned by `state`
+    fn set_state(&mut self, _state: &[bool]) {}
+
+    /// The Graphviz shape of the module's nodes
+    fn shape(&self) -> &'static str {
+        "box"
+    }
+
+    fn clone_box(&self) -> Box<dyn Module>;
+}
+
+impl Clone for Box<dyn Module> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+pub type Constructor = Box<dyn Fn() -> Box<dyn Module>>;
+
+/// The module types the input can declare by the prefix of their names, besides
+/// the puzzle's `%` flip-flops and `&` conjunctions
+#[derive(Default)]
+pub struct Registry {
+    pub(crate) constructors: Vec<(char, Constructor)>,
+}
+
+pub(crate) enum Declared {
+    Builtin(ModuleKind),
+    Custom(Box<dyn Module>),
+}
+
+impl Registry {
+    /// Adds a module type, declared in the input by names starting with `prefix`
+    pub fn register(
+        &mut self,
+        prefix: char,
+        create: impl Fn() -> Box<dyn Module> + 'static,
+    ) -> Result<(), String> {
+        if prefix == '%' || prefix == '&' || prefix.is_alphanumeric() || prefix.is_whitespace() {
+            return Err(format!("`{}` can't be a module prefix", prefix));
+        }
+        if self.constructors.iter().any(|(p, _)| *p == prefix) {
+            return Err(format!("`{}` is already registered", prefix));
+        }
+        self.constructors.push((prefix, Box::new(create)));
+        Ok(())
+    }
+
+    /// What a declaration like `%a -> b` creates, and the module's label
+    pub(crate) fn declare<'a>(&self, input: &'a str) -> (Declared, &'a str) {
+        let name = input.split_whitespace().next().unwrap();
+        let label = self.label(input);
+        let declared = match name.chars().next().unwrap() {
+            '%' => Declared::Builtin(ModuleKind::FlipFlop),
+            '&' => Declared::Builtin(ModuleKind::Conjunction),
+            prefix => match self.constructors.iter().find(|(p, _)| *p == prefix) {
+                Some((_, create)) => Declared::Custom(create()),
+                None => Declared::Builtin(ModuleKind::Broadcaster),
+            },
+        };
+        (declared, label)
+    }
+
+    pub(crate) fn label<'a>(&self, input: &'a str) -> &'a str {
+        let name = input.split_whitespace().next().unwrap();
+        let prefix = name.chars().next().unwrap();
+        let registered = self.constructors.iter().any(|(p, _)| *p == prefix);
+        match prefix == '%' || prefix == '&' || registered {
+            true => &name[prefix.len_utf8()..],
+            false => name,
+        }
+    }
+}
+
+/// Where a module sends its pulses: the receiver, the slot of the edge in the
+/// conjunctions' memory and the number of the edge among the receiver's inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Output {
+    pub to: usize,
+    pub slot: usize,
+    pub input: usize,
+}
@@ -0,0 +1,228 @@
+use super::module::{Module, Pulse};
+
+/// Sends a high pulse on every `modulo`th low pulse and a low pulse on the
+/// others, ignores the high pulses
+#[derive(Debug, Clone)]
+pub struct Divider {
+    modulo: u32,
+    count: u32,
+}
+
+impl Divider {
+    pub fn new(modulo: u32) -> Divider {
+        assert!(modulo > 0);
+        Divider { modulo, count: 0 }
+    }
+
+    fn bits(&self) -> u32 {
+        u32::BITS - (self.modulo - 1).leading_zeros()
+    }
+}
+
+impl Module for Divider {
+    fn receive(&mut self, _input: usize, pulse: Pulse) -> Option<Pulse> {
+        if pulse == Pulse::High {
+            return None;
+        }
+        self.count = (self.count + 1) % self.modulo;
+        match self.count {
+            0 => Some(Pulse::High),
+            _ => Some(Pulse::Low),
+        }
+    }
+
+    fn state(&self) -> Vec<bool> {
+        (0..self.bits())
+            .map(|bit| self.count & (1 << bit) != 0)
+            .collect()
+    }
+
+    fn set_state(&mut self, state: &[bool]) {
+        self.count = state
+            .iter()
+            .enumerate()
+            .filter(|(_, bit)| **bit)
+            .map(|(bit, _)| 1 << bit)
+            .sum();
+    }
+
+    fn shape(&self) -> &'static str {
+        "octagon"
+    }
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
+}
+
+/// Sends the pulse it received before the current one, low the first time
+#[derive(Debug, Clone, Default)]
+pub struct Delay {
+    last: Option<Pulse>,
+}
+
+impl Module for Delay {
+    fn receive(&mut self, _input: usize, pulse: Pulse) -> Option<Pulse> {
+        Some(self.last.replace(pulse).unwrap_or(Pulse::Low))
+    }
+
+    fn state(&self) -> Vec<bool> {
+        vec![self.last.is_some(), self.last == Some(Pulse::High)]
+    }
+
+    fn set_state(&mut self, state: &[bool]) {
+        self.last = match state {
+            [false, _] => None,
+            [true, false] => Some(Pulse::Low),
+            _ => Some(Pulse::High),
+        };
+    }
+
+    fn shape(&self) -> &'static str {
+        "cds"
+    }
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
+}
+
+/// Forwards the pulses of its first input while it's on, every high pulse on
+/// the second input switches it on or off, it starts on
+#[derive(Debug, Clone)]
+pub struct Toggle {
+    on: bool,
+}
+
+impl Default for Toggle {
+    fn default() -> Self {
+        Toggle { on: true }
+    }
+}
+
+impl Module for Toggle {
+    fn receive(&mut self, input: usize, pulse: Pulse) -> Option<Pulse> {
+        match (input, pulse) {
+            (0, pulse) if self.on => Some(pulse),
+            (1, Pulse::High) => {
+                self.on = !self.on;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn state(&self) -> Vec<bool> {
+        vec![self.on]
+    }
+
+    fn set_state(&mut self, state: &[bool]) {
+        self.on = state[0];
+    }
+
+    fn shape(&self) -> &'static str {
+        "diamond"
+    }
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        machine::{module::Registry, Machine},
+        trace::Event,
+    };
+
+    fn registry() -> Registry {
+        let mut registry = Registry::default();
+        registry
+            .register('#', || Box::new(Divider::new(3)))
+            .unwrap();
+        registry.register('~', || Box::<Delay>::default()).unwrap();
+        registry.register('?', || Box::<Toggle>::default()).unwrap();
+        registry
+    }
+
+    fn lines(events: &[Event]) -> Vec<String> {
+        events.iter().map(|event| event.to_string()).collect()
+    }
+
+    #[test]
+    fn test_register_errors() {
+        let mut registry = registry();
+        assert_eq!(
+            registry.register('#', || Box::<Delay>::default()),
+            Err("`#` is already registered".to_string())
+        );
+        assert!(registry.register('%', || Box::<Delay>::default()).is_err());
+        assert!(registry.register('b', || Box::<Delay>::default()).is_err());
+    }
+
+    #[test]
+    fn test_divider() {
+        let mut machine = Machine::with_registry("broadcaster -> c\n#c -> out", &registry());
+        let sent: Vec<String> = (0..4)
+            .map(|_| machine.push_button_traced().last().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            sent,
+            vec![
+                "c -low-> out",
+                "c -low-> out",
+                "c -high-> out",
+                "c -low-> out"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delay_and_toggle() {
+        let input = "broadcaster -> a, t
+            %a -> d
+            ~d -> t
+            ?t -> out";
+        let mut machine = Machine::with_registry(input, &registry());
+        assert_eq!(
+            lines(&machine.push_button_traced()),
+            vec![
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "broadcaster -low-> t",
+                "a -high-> d",
+                "t -low-> out",
+                "d -low-> t",
+            ]
+        );
+        // `d` sends the high pulse of the first press, which switches `t` off
+        let events = machine.push_button_traced();
+        assert_eq!(events.len(), 6);
+        assert_eq!(events[5].to_string(), "d -high-> t");
+        assert_eq!(lines(&machine.push_button_traced()).len(), 5);
+    }
+
+    #[test]
+    fn test_custom_state() {
+        let input = "broadcaster -> c\n#c -> d\n~d -> out";
+        let mut machine = Machine::with_registry(input, &registry());
+        let start = machine.snapshot();
+        machine.push_button();
+        machine.push_button();
+        let pressed = machine.snapshot();
+        assert_eq!(
+            machine.diff(&start, &pressed),
+            vec!["c: 00 -> 01", "d: 00 -> 10"]
+        );
+        machine.restore(&start);
+        assert_eq!(machine.snapshot(), start);
+
+        // the divider's state makes the cycle three presses long
+        let cycle = Machine::with_registry(input, &registry())
+            .find_cycle(10)
+            .unwrap();
+        assert_eq!((cycle.start, cycle.period), (1, 3));
+    }
+}
@@ -1,6 +1,4 @@
-mod machine;
-mod part_1;
-mod part_2;
+use day_20_lib::{part_1, part_2};
 
 fn main() {
     let input = include_str!("./inputs/input.txt");
@@ -0,0 +1,255 @@
+use std::fmt::{self, Display};
+
+use crate::{
+    cycle::State,
+    machine::{
+        module::{ModuleKind, Pulse},
+        Machine,
+    },
+};
+
+/// A pulse sent during a press, prints like the puzzle text: `broadcaster -low-> a`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub from: String,
+    pub pulse: Pulse,
+    pub to: String,
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -{}-> {}", self.from, self.pulse, self.to)
+    }
+}
+
+/// The memory of every module and the counters, taken after a press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub press_count: u32,
+    pub low_pulse_count: u64,
+    pub high_pulse_count: u64,
+    state: State,
+    high_inputs: Vec<usize>,
+    last_high: Vec<u32>,
+}
+
+impl Machine {
+    /// Presses the button, returns every pulse sent, the button's one first,
+    /// nothing when no module receives the button's pulse
+    pub fn push_button_traced(&mut self) -> Vec<Event> {
+        let mut pulses = Vec::new();
+        self.press(|from, pulse, to| pulses.push((from, pulse, to)));
+        let button = self.broadcaster.map(|broadcaster| Event {
+            from: "button".to_string(),
+            pulse: Pulse::Low,
+            to: self.labels[broadcaster].clone(),
+        });
+        let events = pulses.into_iter().map(|(from, pulse, to)| Event {
+            from: self.labels[from].clone(),
+            pulse,
+            to: self.labels[to].clone(),
+        });
+        button.into_iter().chain(events).collect()
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            press_count: self.press_count,
+            low_pulse_count: self.low_pulse_count,
+            high_pulse_count: self.high_pulse_count,
+            state: self.state(),
+            high_inputs: self.high_inputs.clone(),
+            last_high: self.last_high.clone(),
+        }
+    }
+
+    /// Rewinds the machine to the snapshot, which has to be taken from this machine
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        assert_eq!(
+            snapshot.last_high.len(),
+            self.labels.len(),
+            "the snapshot is from another machine"
+        );
+        self.press_count = snapshot.press_count;
+        self.low_pulse_count = snapshot.low_pulse_count;
+        self.high_pulse_count = snapshot.high_pulse_count;
+        self.flip_flops = snapshot.state.flip_flops.clone();
+        self.memory = snapshot.state.memory.clone();
+        for (index, range) in self.custom_ranges().into_iter().enumerate() {
+            self.custom[index].set_state(&snapshot.state.custom[range]);
+        }
+        self.high_inputs = snapshot.high_inputs.clone();
+        self.last_high = snapshot.last_high.clone();
+    }
+
+    /// The memory that changed between two snapshots, a line per module like
+    /// `%a: off -> on`, `&inv: c low -> high` for a conjunction's input or
+    /// `c: 01 -> 10` for the bits of a custom module
+    pub fn diff(&self, before: &Snapshot, after: &Snapshot) -> Vec<String> {
+        let ranges = self.custom_ranges();
+        let mut changes = Vec::new();
+        for (id, label) in self.labels.iter().enumerate() {
+            match self.kinds[id] {
+                ModuleKind::FlipFlop => {
+                    let (was, is) = (
+                        before.state.flip_flops.get(id),
+                        after.state.flip_flops.get(id),
+                    );
+                    if was != is {
+                        changes.push(format!("%{}: {} -> {}", label, on_off(was), on_off(is)));
+                    }
+                }
+                ModuleKind::Conjunction => {
+                    for (from, slot) in &self.inputs[id] {
+                        let (was, is) = (
+                            before.state.memory.get(*slot),
+                            after.state.memory.get(*slot),
+                        );
+                        if was != is {
+                            changes.push(format!(
+                                "&{}: {} {} -> {}",
+                                label,
+                                self.labels[*from],
+                                low_high(was),
+                                low_high(is)
+                            ));
+                        }
+                    }
+                }
+                ModuleKind::Custom(index) => {
+                    let range = ranges[index].clone();
+                    let (was, is) = (
+                        bits(&before.state.custom[range.clone()]),
+                        bits(&after.state.custom[range]),
+                    );
+                    if was != is {
+                        changes.push(format!("{}: {} -> {}", label, was, is));
+                    }
+                }
+                ModuleKind::Broadcaster | ModuleKind::Output => {}
+            }
+        }
+        changes
+    }
+}
+
+fn on_off(on: bool) -> &'static str {
+    match on {
+        true => "on",
+        false => "off",
+    }
+}
+
+fn bits(bits: &[bool]) -> String {
+    bits.iter()
+        .map(|bit| if *bit { '1' } else { '0' })
+        .collect()
+}
+
+fn low_high(high: bool) -> &'static str {
+    match high {
+        true => "high",
+        false => "low",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "broadcaster -> a
+        %a -> inv, con
+        &inv -> b
+        %b -> con
+        &con -> output";
+
+    fn lines(events: &[Event]) -> Vec<String> {
+        events.iter().map(|event| event.to_string()).collect()
+    }
+
+    #[test]
+    fn test_push_button_traced() {
+        let mut machine = Machine::new(EXAMPLE);
+        assert_eq!(
+            lines(&machine.push_button_traced()),
+            vec![
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "a -high-> inv",
+                "a -high-> con",
+                "inv -low-> b",
+                "con -high-> output",
+                "b -high-> con",
+                "con -low-> output",
+            ]
+        );
+        assert_eq!(
+            lines(&machine.push_button_traced()),
+            vec![
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "a -low-> inv",
+                "a -low-> con",
+                "inv -high-> b",
+                "con -high-> output",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_button_pulse_goes_to_the_broadcaster() {
+        let mut machine = Machine::new("start -> a\n%a -> out");
+        assert_eq!(
+            lines(&machine.push_button_traced()),
+            vec!["button -low-> start", "start -low-> a", "a -high-> out"]
+        );
+        let mut machine = Machine::new("%a -> out");
+        assert!(machine.push_button_traced().is_empty());
+    }
+
+    #[test]
+    fn test_trace_counts_like_push_button() {
+        let mut traced = Machine::new(EXAMPLE);
+        let mut pressed = traced.clone();
+        let (mut low, mut high) = (0, 0);
+        for _ in 0..4 {
+            for event in traced.push_button_traced() {
+                match event.pulse {
+                    Pulse::Low => low += 1,
+                    Pulse::High => high += 1,
+                }
+            }
+            pressed.push_button();
+        }
+        assert_eq!((low, high), (17, 11));
+        assert_eq!(traced.snapshot(), pressed.snapshot());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut machine = Machine::new(EXAMPLE);
+        let start = machine.snapshot();
+        machine.push_button();
+        let first = machine.snapshot();
+        let first_events = machine.push_button_traced();
+        machine.push_button();
+        assert_ne!(machine.snapshot(), first);
+
+        machine.restore(&first);
+        assert_eq!(machine.snapshot(), first);
+        assert_eq!(machine.push_button_traced(), first_events);
+
+        assert_eq!(
+            machine.diff(&start, &first),
+            vec![
+                "%a: off -> on",
+                "&inv: a low -> high",
+                "%b: off -> on",
+                "&con: a low -> high",
+                "&con: b low -> high"
+            ]
+        );
+        machine.restore(&start);
+        assert_eq!(machine.snapshot(), start);
+    }
+}
@@ -2,3 +2,4 @@ mod map;
 
 pub mod part_1;
 pub mod part_2;
+pub mod render;
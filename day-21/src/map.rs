@@ -62,7 +62,7 @@ impl Map {
                         possible_next: false,
                     },
                     'S' => {
-                        start = Some((x, y));
+                        start = Some((y, x));
                         Tile {
                             walkable: true,
                             start: true,
@@ -100,33 +100,30 @@ impl Map {
 
     pub fn walk(&mut self, num_of_steps: usize) -> usize {
         let start = self.start_location();
-        let mut count = 0;
-        let on_locations = vec![start].into_iter().collect();
-        self.inner_walk(on_locations, num_of_steps);
-        for row in &self.tiles {
-            for tile in row {
-                if tile.possible_next {
-                    count += 1;
-                }
-            }
-        }
-        self.reset();
-        count
+        self.walk_from_location(start, num_of_steps)
     }
 
     pub fn walk_from_location(&mut self, location: Location, num_of_steps: usize) -> usize {
+        self.reachable_from_location(location, num_of_steps).len()
+    }
+
+    pub fn reachable_from_location(
+        &mut self,
+        location: Location,
+        num_of_steps: usize,
+    ) -> Vec<Location> {
         let on_locations = vec![location].into_iter().collect();
         self.inner_walk(on_locations, num_of_steps);
-        let mut count = 0;
-        for row in &self.tiles {
-            for tile in row {
+        let mut reachable = Vec::new();
+        for (i, row) in self.tiles.iter().enumerate() {
+            for (j, tile) in row.iter().enumerate() {
                 if tile.possible_next {
-                    count += 1;
+                    reachable.push(Location { i, j });
                 }
             }
         }
         self.reset();
-        count
+        reachable
     }
 
     pub fn start_location(&self) -> Location {
@@ -141,7 +138,7 @@ impl Map {
             for location in on_locations {
                 let possible_locations = self.check_possible_moves(location);
                 for possible_location in possible_locations {
-                    self.tiles[possible_location.j][possible_location.i].possible_next = true;
+                    self.tiles[possible_location.i][possible_location.j].possible_next = true;
                 }
             }
             return;
@@ -285,4 +282,16 @@ mod tests {
         println!("{}", map);
         assert_eq!(result, 16);
     }
+
+    #[test]
+    fn test_start_off_the_diagonal() {
+        // the start is stored as (row, column) and the tiles are marked by row,
+        // a wide map with the start in a corner would index out of bounds otherwise
+        let input = "#..S\n....\n";
+        let mut map = Map::new(input);
+        let start = map.start_location();
+        assert_eq!(start, Location::new(0, 3));
+        let reachable = map.reachable_from_location(start, 0);
+        assert_eq!(reachable, vec![Location::new(0, 2), Location::new(1, 3)]);
+    }
 }
@@ -3,6 +3,13 @@ use crate::map::{Location, Map};
 const NUMBER_OF_STEPS: usize = 26501364;
 
 pub fn process(input: &str) -> usize {
+    let (result, _) = garden_plots(input);
+    result
+}
+
+/// Garden plots reached on the infinite map, and the plots reached on a fully explored
+/// copy of the garden after an odd number of steps, the copy the result is built from
+pub(crate) fn garden_plots(input: &str) -> (usize, Vec<Location>) {
     let mut map = Map::new(input);
     let size = map.size();
     let start = map.start_location();
@@ -12,7 +19,8 @@ pub fn process(input: &str) -> usize {
 
     let even = square((grid_width + 1) / 2 * 2);
 
-    let odd_points = map.walk(size * 2);
+    let odd_plots = map.reachable_from_location(start, size * 2);
+    let odd_points = odd_plots.len();
 
     let even_points = map.walk(size * 2 - 1);
 
@@ -40,7 +48,7 @@ pub fn process(input: &str) -> usize {
     let large = grid_width * large_sum;
 
     let result = odd + even + corner_sum + small + large;
-    result
+    (result, odd_plots)
 }
 
 fn square(size: usize) -> usize {
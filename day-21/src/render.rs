@@ -0,0 +1,48 @@
+use crate::{
+    map::{Location, Map},
+    part_2,
+};
+
+/// The garden plots reachable in exactly 64 steps
+pub fn reachable_plots(input: &str) -> (usize, Vec<(usize, usize)>) {
+    let mut map = Map::new(input);
+    let start = map.start_location();
+    let plots = to_tuples(map.reachable_from_location(start, 63));
+    (plots.len(), plots)
+}
+
+/// Garden plots reachable on the infinite map, and the plots of the odd copies
+/// of the garden the answer is built from
+pub fn odd_garden_plots(input: &str) -> (usize, Vec<(usize, usize)>) {
+    let (result, plots) = part_2::garden_plots(input);
+    (result, to_tuples(plots))
+}
+
+fn to_tuples(locations: Vec<Location>) -> Vec<(usize, usize)> {
+    locations
+        .iter()
+        .map(|location| (location.i, location.j))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reachable_plots() {
+        let input = include_str!("./inputs/test.txt");
+        let (count, plots) = reachable_plots(input);
+        assert_eq!(count, crate::part_1::process(input));
+        assert_eq!(plots.len(), count);
+        assert!(plots.contains(&(5, 5)));
+    }
+
+    #[test]
+    fn test_odd_garden_plots() {
+        let input = include_str!("./inputs/test.txt");
+        let (_, plots) = odd_garden_plots(input);
+        assert!(!plots.contains(&(5, 5)));
+        assert!(plots.contains(&(5, 4)));
+    }
+}
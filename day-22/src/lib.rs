@@ -1,4 +1,4 @@
-mod tower;
+pub mod tower;
 
 pub mod part_1;
 pub mod part_2;
@@ -1,53 +1,27 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
-use self::brick::Brick;
+pub use self::brick::Brick;
+pub use self::support::{ChainReaction, Supports};
 
 mod brick;
+mod support;
+#[cfg(test)]
+mod voxel;
 
 #[derive(Clone)]
 pub struct Tower {
-    tower: Vec<Vec<Vec<Option<Brick>>>>,
     bricks: Vec<Brick>,
+    // For each brick the ones it rests on, filled when gravity is applied
+    below: Vec<Vec<usize>>,
     unbreakable: Vec<usize>,
 }
 
 impl Tower {
     pub fn new(input: &str) -> Tower {
-        let mut brick_queue = Vec::new();
-        let mut layer = 0;
-        let mut column = 0;
-        let mut row = 0;
-        for line in input.lines() {
-            let brick = Brick::new(line);
-            if brick.first_end.2 > layer {
-                layer = brick.first_end.2;
-            }
-            if brick.first_end.1 > column {
-                column = brick.first_end.1;
-            }
-            if brick.first_end.0 > row {
-                row = brick.first_end.0;
-            }
-            if brick.second_end.2 > layer {
-                layer = brick.second_end.2;
-            }
-            if brick.second_end.1 > column {
-                column = brick.second_end.1;
-            }
-            if brick.second_end.0 > row {
-                row = brick.second_end.0;
-            }
-            brick_queue.push(brick);
-        }
-        let mut base = create_cube(layer, row, column);
-        let bricks = brick_queue.clone();
-        for brick in brick_queue {
-            insert_in_correct_place(brick, &mut base);
-        }
-
+        let bricks = input.lines().map(Brick::new).collect();
         Tower {
-            tower: base,
             bricks,
+            below: Vec::new(),
             unbreakable: Vec::new(),
         }
     }
@@ -55,276 +29,109 @@ impl Tower {
     //Returns the number of bricks that were dropped
     pub fn apply_gravity(&mut self) -> usize {
         let mut count = 0;
-        let mut bricks = std::mem::replace(&mut self.bricks, Vec::new());
+        let mut bricks = std::mem::take(&mut self.bricks);
         bricks.sort();
-        for mut brick in bricks {
-            let mut dropped = false;
-            while !self.was_brick_bellow(&brick) {
-                dropped = true;
-                self.move_brick_down(&mut brick);
-            }
-            if dropped {
+        // Top "z" and the brick owning it for every (x, y) covered so far
+        let mut heights: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        let mut below = Vec::with_capacity(bricks.len());
+        for (i, brick) in bricks.iter_mut().enumerate() {
+            let cells = footprint(brick);
+            let rest = cells
+                .iter()
+                .filter_map(|cell| heights.get(cell))
+                .map(|(z, _)| *z)
+                .max();
+            let mut holders: Vec<usize> = cells
+                .iter()
+                .filter_map(|cell| heights.get(cell))
+                .filter(|(z, _)| Some(*z) == rest)
+                .map(|(_, owner)| *owner)
+                .collect();
+            holders.sort();
+            holders.dedup();
+            below.push(holders);
+
+            let z = rest.map_or(0, |z| z + 1);
+            if z != brick.first_end.2 {
                 count += 1;
+                brick.second_end.2 -= brick.first_end.2 - z;
+                brick.first_end.2 = z;
             }
-            self.bricks.push(brick);
-        }
-        self.bricks.sort();
-        count
-    }
-
-    //Returns the number of bricks that can be destroyed
-    pub fn mark_unbreakable(&mut self) -> usize {
-        let mut count = 0;
-        std::mem::swap(&mut self.unbreakable, &mut Vec::new());
-        for (i, brick) in self.bricks.iter().enumerate() {
-            let bricks_above = self.get_bricks_above(brick);
-            if bricks_above.len() == 0 {
-                count += 1;
-            } else {
-                let mut count_bricks: HashMap<Brick, usize> = HashMap::new();
-                for brick_above in bricks_above {
-                    count_bricks.entry(brick_above).or_insert(0);
-                }
-                let same_line_bricks = self.get_same_line_bricks(brick);
-                for same_line_brick in same_line_bricks {
-                    let same_line_bricks_above = self.get_bricks_above(&same_line_brick);
-                    for same_line_brick_above in same_line_bricks_above {
-                        if let Some(count) = count_bricks.get_mut(&same_line_brick_above) {
-                            *count += 1;
-                        }
-                    }
-                }
-                let mut destroyable = true;
-                for (_, count) in count_bricks {
-                    if count == 0 {
-                        destroyable = false;
-                        break;
-                    }
-                }
-                if destroyable {
-                    count += 1;
-                } else {
-                    self.unbreakable.push(i);
-                }
+            for cell in cells {
+                heights.insert(cell, (brick.second_end.2, i));
             }
         }
-        count
-    }
 
-    pub fn count_drops(&self) -> usize {
-        let mut count = 0;
-        for i in 0..self.unbreakable.len() {
-            let mut tower = self.clone();
-            let removed = self.unbreakable[i];
-            tower.remove_brick(removed);
-            let dropped = tower.apply_gravity();
-            count += dropped;
+        // Sort again by where the bricks ended up, renaming the holders to match
+        let mut order: Vec<usize> = (0..bricks.len()).collect();
+        order.sort_by_key(|&i| bricks[i]);
+        let mut rank = vec![0; bricks.len()];
+        for (new, &old) in order.iter().enumerate() {
+            rank[old] = new;
         }
+        self.bricks = order.iter().map(|&i| bricks[i]).collect();
+        self.below = order
+            .iter()
+            .map(|&i| {
+                let mut holders: Vec<usize> = below[i].iter().map(|&h| rank[h]).collect();
+                holders.sort();
+                holders
+            })
+            .collect();
         count
     }
 
-    fn remove_brick(&mut self, i: usize) {
-        let brick = self.bricks.remove(i);
-        self.remove_brick_from_tower(brick);
-    }
-
-    fn remove_brick_from_tower(&mut self, brick: Brick) {
-        let mut z = brick.first_end.2;
-        let mut y = brick.first_end.1;
-        let mut x = brick.first_end.0;
-        let z_target = brick.second_end.2;
-        let y_target = brick.second_end.1;
-        let x_target = brick.second_end.0;
-        while z <= z_target {
-            while x <= x_target {
-                while y <= y_target {
-                    self.tower[z][x][y] = None;
-                    y += 1;
-                }
-                y = brick.first_end.1;
-                x += 1;
-            }
-            x = brick.first_end.0;
-            z += 1;
-        }
+    //Returns the number of bricks that can be destroyed
+    pub fn mark_unbreakable(&mut self) -> usize {
+        let supports = self.supports();
+        self.unbreakable = (0..self.bricks.len())
+            .filter(|&i| {
+                supports
+                    .above(i)
+                    .iter()
+                    .any(|&up| supports.below(up).len() == 1)
+            })
+            .collect();
+        self.bricks.len() - self.unbreakable.len()
     }
 
-    // "z" is the line
-    fn get_same_line_bricks(&self, brick: &Brick) -> Vec<Brick> {
-        let z = brick.second_end.2;
-        let x_len = self.tower[0].len();
-        let y_len = self.tower[0][0].len();
-
-        let mut bricks = HashSet::new();
-        for x in 0..x_len {
-            for y in 0..y_len {
-                if let Some(b) = self.tower[z][x][y] {
-                    if b != *brick && b.second_end.2 == z {
-                        bricks.insert(b);
-                    }
-                }
-            }
-        }
-
-        bricks.into_iter().collect()
+    //Returns the sum of the bricks that fall for each brick removed
+    pub fn count_drops(&self) -> usize {
+        self.supports().chain_reaction().total()
     }
 
-    //Above is going down the tower
-    fn get_bricks_above(&self, brick: &Brick) -> HashSet<Brick> {
-        let z = brick.second_end.2;
-        let mut y = brick.first_end.1;
-        let mut x = brick.first_end.0;
-        let y_target = brick.second_end.1;
-        let x_target = brick.second_end.0;
-        let mut bricks = HashSet::new();
-        if z == self.tower.len() - 1 {
-            return bricks.into_iter().collect();
-        }
-
-        while x <= x_target {
-            while y <= y_target {
-                if let Some(b) = self.tower[z + 1][x][y] {
-                    bricks.insert(b);
-                }
-                y += 1;
-            }
-            y = brick.first_end.1;
-            x += 1;
-        }
-
-        let bricks: HashSet<Brick> = bricks.into_iter().collect();
-        bricks
+    pub fn bricks(&self) -> &[Brick] {
+        &self.bricks
     }
 
-    //Bellow is going up the tower
-    fn was_brick_bellow(&self, brick: &Brick) -> bool {
-        let z = brick.first_end.2;
-        let mut y = brick.first_end.1;
-        let mut x = brick.first_end.0;
-        let y_target = brick.second_end.1;
-        let x_target = brick.second_end.0;
-        if z == 0 {
-            return true;
-        }
-
-        while x <= x_target {
-            while y <= y_target {
-                if let Some(_) = self.tower[z - 1][x][y] {
-                    return true;
-                }
-                y += 1;
-            }
-            y = brick.first_end.1;
-            x += 1;
-        }
-
-        false
-    }
-
-    fn move_brick_down(&mut self, brick: &mut Brick) {
-        self.remove_brick_from_tower(*brick);
-
-        //Insert brick in new place
-        brick.first_end.2 -= 1;
-        brick.second_end.2 -= 1;
-
-        let mut z = brick.first_end.2;
-        let mut y = brick.first_end.1;
-        let mut x = brick.first_end.0;
-        let z_target = brick.second_end.2;
-        let y_target = brick.second_end.1;
-        let x_target = brick.second_end.0;
-        while z <= z_target {
-            while x <= x_target {
-                while y <= y_target {
-                    if self.tower[z][x][y].is_some() {
-                        panic!("Overlapping bricks");
-                    }
-                    self.tower[z][x][y] = Some(*brick);
-                    y += 1;
-                }
-                y = brick.first_end.1;
-                x += 1;
-            }
-            x = brick.first_end.0;
-            z += 1;
-        }
-    }
-}
-
-fn insert_in_correct_place(brick: Brick, tower: &mut Vec<Vec<Vec<Option<Brick>>>>) {
-    let mut z = brick.first_end.2;
-    let mut y = brick.first_end.1;
-    let mut x = brick.first_end.0;
-    let z_target = brick.second_end.2;
-    let y_target = brick.second_end.1;
-    let x_target = brick.second_end.0;
-    while z <= z_target {
-        while x <= x_target {
-            while y <= y_target {
-                tower[z][x][y] = Some(brick);
-                y += 1;
-            }
-            y = brick.first_end.1;
-            x += 1;
-        }
-        x = brick.first_end.0;
-        z += 1;
+    /// Which bricks rest on which, the tower has to be settled first
+    pub fn supports(&self) -> Supports {
+        assert_eq!(
+            self.below.len(),
+            self.bricks.len(),
+            "the tower has to be settled first"
+        );
+        // Settling keeps the bricks sorted by "z", so holders come first
+        Supports::new(self.below.clone(), (0..self.bricks.len()).collect())
     }
 }
 
-// Z is the line, x is the depth, y is the column
-fn create_cube(z: usize, mut x: usize, mut y: usize) -> Vec<Vec<Vec<Option<Brick>>>> {
-    if x > y {
-        y = x;
-    } else if y > x {
-        x = y;
-    }
-    let mut cube = Vec::new();
-    for _ in 0..z + 1 {
-        let mut layer = Vec::new();
-        for _ in 0..x + 1 {
-            let mut row = Vec::new();
-            for _ in 0..y + 1 {
-                row.push(None);
-            }
-            layer.push(row);
+// The (x, y) cells under a brick
+fn footprint(brick: &Brick) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for x in brick.first_end.0..=brick.second_end.0 {
+        for y in brick.first_end.1..=brick.second_end.1 {
+            cells.push((x, y));
         }
-        cube.push(layer);
     }
-    cube
+    cells
 }
 
 #[cfg(test)]
 mod test {
+    use super::voxel::VoxelTower;
     use super::*;
 
-    #[test]
-    fn test_insert_in_correct_place() {
-        let input = "1,0,1~1,2,1";
-        let brick = Brick::new(input);
-
-        let mut column = 0;
-        let mut row = 0;
-
-        if brick.first_end.1 > column {
-            column = brick.first_end.1;
-        }
-        if brick.first_end.0 > row {
-            row = brick.first_end.0;
-        }
-        if brick.second_end.1 > column {
-            column = brick.second_end.1;
-        }
-        if brick.second_end.0 > row {
-            row = brick.second_end.0;
-        }
-
-        let mut base = create_cube(9, row, column);
-
-        insert_in_correct_place(brick, &mut base);
-    }
-
     #[test]
     fn test_gravity() {
         let input = include_str!("../inputs/test.txt");
@@ -341,19 +148,6 @@ mod test {
         tower.apply_gravity();
     }
 
-    #[test]
-    fn test_same_line() {
-        let input = include_str!("../inputs/test.txt");
-        let mut tower = Tower::new(input);
-        tower.apply_gravity();
-        let first_brick = tower.bricks[0];
-        let bricks = tower.get_same_line_bricks(&first_brick);
-        assert!(bricks.len() == 0);
-        let second_brick = tower.bricks[1];
-        let bricks = tower.get_same_line_bricks(&second_brick);
-        assert_eq!(bricks.len(), 1);
-    }
-
     #[test]
     fn test_destroyable() {
         let input = include_str!("../inputs/test.txt");
@@ -375,4 +169,108 @@ mod test {
         assert_eq!(destroyable, 454);
         assert_eq!(tower.unbreakable.len(), 871);
     }
+
+    const EXAMPLE: &str = "1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+    #[test]
+    fn test_supports() {
+        let mut tower = Tower::new(EXAMPLE);
+        tower.apply_gravity();
+        let supports = tower.supports();
+        assert!(supports.on_ground(0));
+        assert_eq!(supports.above(0), &[1, 2]);
+        assert_eq!(supports.below(3), &[1, 2]);
+        assert_eq!(supports.below(5), &[3, 4]);
+        assert_eq!(supports.above(6), &[] as &[usize]);
+        assert_eq!(supports.disintegrable(), tower.mark_unbreakable());
+    }
+
+    #[test]
+    fn test_chain_reaction_matches_gravity() {
+        let mut tower = Tower::new(EXAMPLE);
+        tower.apply_gravity();
+        let mut voxel = VoxelTower::new(EXAMPLE);
+        voxel.apply_gravity();
+        let chain = tower.supports().chain_reaction();
+        for i in 0..tower.bricks.len() {
+            assert_eq!(chain.falls(i), voxel.drops_without(i));
+        }
+        assert_eq!(chain.falling(0), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(chain.falling(5), vec![6]);
+        assert_eq!(tower.count_drops(), 7);
+    }
+
+    // Bricks on a small floor so they pile up, each one above the last so
+    // none of them overlap before falling
+    fn random_tower(seed: u64, count: usize, floor: usize) -> String {
+        let mut state = seed;
+        let mut next = |max: usize| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as usize % max
+        };
+        let mut z = 1;
+        let mut lines = Vec::new();
+        for _ in 0..count {
+            let (x, y) = (next(floor), next(floor));
+            let (mut dx, mut dy, mut dz) = (0, 0, 0);
+            match next(3) {
+                0 => dx = next(floor - x),
+                1 => dy = next(floor - y),
+                _ => dz = next(3),
+            }
+            lines.push(format!(
+                "{},{},{}~{},{},{}",
+                x,
+                y,
+                z,
+                x + dx,
+                y + dy,
+                z + dz
+            ));
+            z += dz + 1 + next(3);
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_settles_like_voxels() {
+        for seed in 0..20 {
+            let input = random_tower(seed, 60, 5);
+            let mut tower = Tower::new(&input);
+            let mut voxel = VoxelTower::new(&input);
+            assert_eq!(tower.apply_gravity(), voxel.apply_gravity());
+            assert_eq!(tower.bricks, voxel.bricks);
+
+            let chain = tower.supports().chain_reaction();
+            for i in 0..tower.bricks.len() {
+                assert_eq!(chain.falls(i), voxel.drops_without(i), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_far_coordinates() {
+        let input = "1000000,2000000,3000000~1000002,2000000,3000000
+1000002,2000000,3500000~1000002,2000004,3500000
+1000002,2000004,4000000~1000002,2000004,4000009
+5000000,5000000,5000000~5000000,5000000,5000000";
+        let mut tower = Tower::new(input);
+        assert_eq!(tower.apply_gravity(), 4);
+        let bottoms: Vec<(usize, usize)> = tower
+            .bricks()
+            .iter()
+            .map(|brick| (brick.first_end.2, brick.second_end.2))
+            .collect();
+        assert_eq!(bottoms, vec![(0, 0), (0, 0), (1, 1), (2, 11)]);
+        assert_eq!(tower.supports().below(3), &[2]);
+        assert_eq!(tower.count_drops(), 3);
+    }
 }
@@ -0,0 +1,207 @@
+/// Which bricks rest on which in a settled tower, indexed like `Tower::bricks`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Supports {
+    below: Vec<Vec<usize>>,
+    above: Vec<Vec<usize>>,
+    // Indices sorted so every brick comes after the ones holding it
+    order: Vec<usize>,
+}
+
+impl Supports {
+    pub(crate) fn new(below: Vec<Vec<usize>>, order: Vec<usize>) -> Supports {
+        let mut above = vec![Vec::new(); below.len()];
+        for (i, holders) in below.iter().enumerate() {
+            for &holder in holders {
+                above[holder].push(i);
+            }
+        }
+        Supports {
+            below,
+            above,
+            order,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.below.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.below.is_empty()
+    }
+
+    /// The bricks brick `i` rests on, empty if it is on the ground
+    pub fn below(&self, i: usize) -> &[usize] {
+        &self.below[i]
+    }
+
+    /// The bricks resting on brick `i`
+    pub fn above(&self, i: usize) -> &[usize] {
+        &self.above[i]
+    }
+
+    pub fn on_ground(&self, i: usize) -> bool {
+        self.below[i].is_empty()
+    }
+
+    /// Bricks that can be removed without anything falling
+    pub fn disintegrable(&self) -> usize {
+        (0..self.len())
+            .filter(|&i| self.above[i].iter().all(|&up| self.below[up].len() > 1))
+            .count()
+    }
+
+    /// Builds the dominator tree of the supports rooted at the ground: a brick
+    /// falls when any of its dominators is removed
+    pub fn chain_reaction(&self) -> ChainReaction {
+        let len = self.len();
+        let ground = len;
+        let levels = usize::BITS as usize - len.leading_zeros() as usize + 1;
+        // up[k][v] is the 2^k-th dominator of v, the ground points to itself
+        let mut up = vec![vec![ground; len + 1]; levels];
+        let mut depth = vec![0; len + 1];
+        let mut holder = vec![None; len];
+
+        for &i in &self.order {
+            let idom = self.below[i]
+                .iter()
+                .copied()
+                .reduce(|a, b| lca(&up, &depth, a, b))
+                .unwrap_or(ground);
+            depth[i] = depth[idom] + 1;
+            up[0][i] = idom;
+            for k in 1..levels {
+                up[k][i] = up[k - 1][up[k - 1][i]];
+            }
+            if idom != ground {
+                holder[i] = Some(idom);
+            }
+        }
+
+        let mut children = vec![Vec::new(); len];
+        let mut sizes = vec![1; len];
+        for &i in self.order.iter().rev() {
+            if let Some(parent) = holder[i] {
+                children[parent].push(i);
+                sizes[parent] += sizes[i];
+            }
+        }
+        for brick in &mut children {
+            brick.reverse();
+        }
+
+        ChainReaction {
+            holder,
+            children,
+            sizes,
+        }
+    }
+}
+
+fn lca(up: &[Vec<usize>], depth: &[usize], mut a: usize, mut b: usize) -> usize {
+    if depth[a] < depth[b] {
+        std::mem::swap(&mut a, &mut b);
+    }
+    let mut diff = depth[a] - depth[b];
+    let mut k = 0;
+    while diff > 0 {
+        if diff & 1 == 1 {
+            a = up[k][a];
+        }
+        diff >>= 1;
+        k += 1;
+    }
+    if a == b {
+        return a;
+    }
+    for k in (0..up.len()).rev() {
+        if up[k][a] != up[k][b] {
+            a = up[k][a];
+            b = up[k][b];
+        }
+    }
+    up[0][a]
+}
+
+/// The dominator tree of a tower's supports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainReaction {
+    holder: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    // Bricks in each subtree, the brick itself included
+    sizes: Vec<usize>,
+}
+
+impl ChainReaction {
+    /// The closest brick every path from brick `i` to the ground goes through,
+    /// `None` when only the ground holds it
+    pub fn holder(&self, i: usize) -> Option<usize> {
+        self.holder[i]
+    }
+
+    /// How many other bricks fall if brick `i` is removed
+    pub fn falls(&self, i: usize) -> usize {
+        self.sizes[i] - 1
+    }
+
+    /// Sum of the bricks falling for every brick removed alone
+    pub fn total(&self) -> usize {
+        (0..self.sizes.len()).map(|i| self.falls(i)).sum()
+    }
+
+    /// The bricks that fall if brick `i` is removed, sorted
+    pub fn falling(&self, i: usize) -> Vec<usize> {
+        let mut falling = Vec::with_capacity(self.falls(i));
+        let mut stack = self.children[i].clone();
+        while let Some(brick) = stack.pop() {
+            falling.push(brick);
+            stack.extend(&self.children[brick]);
+        }
+        falling.sort();
+        falling
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 0 holds 1 and 2, both hold 3, 3 holds 4 and 4 holds 5 and 6
+    fn diamond() -> Supports {
+        let below = vec![
+            vec![],
+            vec![0],
+            vec![0],
+            vec![1, 2],
+            vec![3],
+            vec![4],
+            vec![4],
+        ];
+        Supports::new(below, (0..7).collect())
+    }
+
+    #[test]
+    fn test_above() {
+        let supports = diamond();
+        assert_eq!(supports.above(0), &[1, 2]);
+        assert_eq!(supports.above(3), &[4]);
+        assert!(supports.on_ground(0));
+        assert!(!supports.on_ground(3));
+        assert_eq!(supports.disintegrable(), 4);
+    }
+
+    #[test]
+    fn test_chain_reaction() {
+        let chain = diamond().chain_reaction();
+        assert_eq!(chain.holder(0), None);
+        assert_eq!(chain.holder(3), Some(0));
+        assert_eq!(chain.holder(5), Some(4));
+        assert_eq!(chain.falls(0), 6);
+        assert_eq!(chain.falls(1), 0);
+        assert_eq!(chain.falls(3), 3);
+        assert_eq!(chain.total(), 6 + 3 + 2);
+        assert_eq!(chain.falling(0), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(chain.falling(3), vec![4, 5, 6]);
+        assert!(chain.falling(6).is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::HashSet;
+
+use super::brick::Brick;
+
+// The first way the tower was settled, a brick at a time through a dense
+// grid of cells. Kept to check the height map settling against
+#[derive(Clone)]
+pub struct VoxelTower {
+    tower: Vec<Vec<Vec<Option<Brick>>>>,
+    pub bricks: Vec<Brick>,
+}
+
+impl VoxelTower {
+    pub fn new(input: &str) -> VoxelTower {
+        let mut brick_queue = Vec::new();
+        let mut layer = 0;
+        let mut column = 0;
+        let mut row = 0;
+        for line in input.lines() {
+            let brick = Brick::new(line);
+            if brick.first_end.2 > layer {
+                layer = brick.first_end.2;
+            }
+            if brick.first_end.1 > column {
+                column = brick.first_end.1;
+            }
+            if brick.first_end.0 > row {
+                row = brick.first_end.0;
+            }
+            if brick.second_end.2 > layer {
+                layer = brick.second_end.2;
+            }
+            if brick.second_end.1 > column {
+                column = brick.second_end.1;
+            }
+            if brick.second_end.0 > row {
+                row = brick.second_end.0;
+            }
+            brick_queue.push(brick);
+        }
+        let mut base = create_cube(layer, row, column);
+        let bricks = brick_queue.clone();
+        for brick in brick_queue {
+            insert_in_correct_place(brick, &mut base);
+        }
+
+        VoxelTower {
+            tower: base,
+            bricks,
+        }
+    }
+
+    //Returns the number of bricks that were dropped
+    pub fn apply_gravity(&mut self) -> usize {
+        let mut count = 0;
+        let mut bricks = std::mem::take(&mut self.bricks);
+        bricks.sort();
+        for mut brick in bricks {
+            let mut dropped = false;
+            while !self.was_brick_bellow(&brick) {
+                dropped = true;
+                self.move_brick_down(&mut brick);
+            }
+            if dropped {
+                count += 1;
+            }
+            self.bricks.push(brick);
+        }
+        self.bricks.sort();
+        count
+    }
+
+    //Returns the number of bricks that fall without brick "i"
+    pub fn drops_without(&self, i: usize) -> usize {
+        let mut tower = self.clone();
+        let brick = tower.bricks.remove(i);
+        tower.remove_brick_from_tower(brick);
+        tower.apply_gravity()
+    }
+
+    fn remove_brick_from_tower(&mut self, brick: Brick) {
+        let mut z = brick.first_end.2;
+        let mut y = brick.first_end.1;
+        let mut x = brick.first_end.0;
+        let z_target = brick.second_end.2;
+        let y_target = brick.second_end.1;
+        let x_target = brick.second_end.0;
+        while z <= z_target {
+            while x <= x_target {
+                while y <= y_target {
+                    self.tower[z][x][y] = None;
+                    y += 1;
+                }
+                y = brick.first_end.1;
+                x += 1;
+            }
+            x = brick.first_end.0;
+            z += 1;
+        }
+    }
+
+    // "z" is the line
+    fn get_same_line_bricks(&self, brick: &Brick) -> Vec<Brick> {
+        let z = brick.second_end.2;
+        let x_len = self.tower[0].len();
+        let y_len = self.tower[0][0].len();
+
+        let mut bricks = HashSet::new();
+        for x in 0..x_len {
+            for y in 0..y_len {
+                if let Some(b) = self.tower[z][x][y] {
+                    if b != *brick && b.second_end.2 == z {
+                        bricks.insert(b);
+                    }
+                }
+            }
+        }
+
+        bricks.into_iter().collect()
+    }
+
+    fn was_brick_bellow(&self, brick: &Brick) -> bool {
+        let z = brick.first_end.2;
+        let mut y = brick.first_end.1;
+        let mut x = brick.first_end.0;
+        let y_target = brick.second_end.1;
+        let x_target = brick.second_end.0;
+        if z == 0 {
+            return true;
+        }
+
+        while x <= x_target {
+            while y <= y_target {
+                if self.tower[z - 1][x][y].is_some() {
+                    return true;
+                }
+                y += 1;
+            }
+            y = brick.first_end.1;
+            x += 1;
+        }
+
+        false
+    }
+
+    fn move_brick_down(&mut self, brick: &mut Brick) {
+        self.remove_brick_from_tower(*brick);
+
+        //Insert brick in new place
+        brick.first_end.2 -= 1;
+        brick.second_end.2 -= 1;
+
+        let mut z = brick.first_end.2;
+        let mut y = brick.first_end.1;
+        let mut x = brick.first_end.0;
+        let z_target = brick.second_end.2;
+        let y_target = brick.second_end.1;
+        let x_target = brick.second_end.0;
+        while z <= z_target {
+            while x <= x_target {
+                while y <= y_target {
+                    if self.tower[z][x][y].is_some() {
+                        panic!("Overlapping bricks");
+                    }
+                    self.tower[z][x][y] = Some(*brick);
+                    y += 1;
+                }
+                y = brick.first_end.1;
+                x += 1;
+            }
+            x = brick.first_end.0;
+            z += 1;
+        }
+    }
+}
+
+fn insert_in_correct_place(brick: Brick, tower: &mut [Vec<Vec<Option<Brick>>>]) {
+    let mut z = brick.first_end.2;
+    let mut y = brick.first_end.1;
+    let mut x = brick.first_end.0;
+    let z_target = brick.second_end.2;
+    let y_target = brick.second_end.1;
+    let x_target = brick.second_end.0;
+    while z <= z_target {
+        while x <= x_target {
+            while y <= y_target {
+                tower[z][x][y] = Some(brick);
+                y += 1;
+            }
+            y = brick.first_end.1;
+            x += 1;
+        }
+        x = brick.first_end.0;
+        z += 1;
+    }
+}
+
+// Z is the line, x is the depth, y is the column
+fn create_cube(z: usize, mut x: usize, mut y: usize) -> Vec<Vec<Vec<Option<Brick>>>> {
+    if x > y {
+        y = x;
+    } else if y > x {
+        x = y;
+    }
+    let mut cube = Vec::new();
+    for _ in 0..z + 1 {
+        let mut layer = Vec::new();
+        for _ in 0..x + 1 {
+            let mut row = Vec::new();
+            for _ in 0..y + 1 {
+                row.push(None);
+            }
+            layer.push(row);
+        }
+        cube.push(layer);
+    }
+    cube
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_insert_in_correct_place() {
+        let input = "1,0,1~1,2,1";
+        let brick = Brick::new(input);
+
+        let mut column = 0;
+        let mut row = 0;
+
+        if brick.first_end.1 > column {
+            column = brick.first_end.1;
+        }
+        if brick.first_end.0 > row {
+            row = brick.first_end.0;
+        }
+        if brick.second_end.1 > column {
+            column = brick.second_end.1;
+        }
+        if brick.second_end.0 > row {
+            row = brick.second_end.0;
+        }
+
+        let mut base = create_cube(9, row, column);
+
+        insert_in_correct_place(brick, &mut base);
+    }
+
+    #[test]
+    fn test_same_line() {
+        let input = include_str!("../inputs/test.txt");
+        let mut tower = VoxelTower::new(input);
+        tower.apply_gravity();
+        let first_brick = tower.bricks[0];
+        let bricks = tower.get_same_line_bricks(&first_brick);
+        assert!(bricks.is_empty());
+        let second_brick = tower.bricks[1];
+        let bricks = tower.get_same_line_bricks(&second_brick);
+        assert_eq!(bricks.len(), 1);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod part_1;
 pub mod part_2;
+pub mod render;
@@ -1,4 +1,4 @@
-use std::{collections::BinaryHeap, fmt::Display};
+use std::fmt::Display;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 enum Direction {
@@ -10,13 +10,13 @@ enum Direction {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct Tile {
-    i: usize,
-    j: usize,
+pub(crate) struct Tile {
+    pub(crate) i: usize,
+    pub(crate) j: usize,
     direction: Direction,
 }
 
-struct Map {
+pub(crate) struct Map {
     tiles: Vec<Vec<Option<Tile>>>,
 }
 
@@ -62,9 +62,23 @@ impl Map {
     }
 
     pub fn walk_longest(&self, start: Tile, end: (usize, usize)) -> usize {
-        let mut heap = BinaryHeap::new();
-        self.inner_walk(start, end, start, 0, &mut heap);
-        heap.pop().unwrap()
+        self.longest_hike(start, end).len() - 1
+    }
+
+    //The tiles of the longest hike, including the start and the end
+    pub fn longest_hike(&self, start: Tile, end: (usize, usize)) -> Vec<Tile> {
+        let mut path = vec![start];
+        let mut longest = Vec::new();
+        self.inner_walk(start, end, start, &mut path, &mut longest);
+        longest
+    }
+
+    pub fn start_and_end(&self) -> (Tile, (usize, usize)) {
+        let start = self.get_tile(0, 1).unwrap();
+        let i_len = self.tiles.len();
+        let j_len = self.tiles[0].len();
+        let end = (i_len - 1, j_len - 2);
+        (start, end)
     }
 
     fn inner_walk(
@@ -72,16 +86,20 @@ impl Map {
         start: Tile,
         end: (usize, usize),
         coming_from: Tile,
-        steps: usize,
-        heap: &mut BinaryHeap<usize>,
+        path: &mut Vec<Tile>,
+        longest: &mut Vec<Tile>,
     ) {
         let options = self.get_possible_tiles(start, coming_from);
         for option in options {
+            path.push(option);
             if option.i == end.0 && option.j == end.1 {
-                heap.push(steps + 1);
+                if path.len() > longest.len() {
+                    *longest = path.clone();
+                }
             } else {
-                self.inner_walk(option, end, start, steps + 1, heap);
+                self.inner_walk(option, end, start, path, longest);
             }
+            path.pop();
         }
     }
 
@@ -198,10 +216,7 @@ impl Display for Map {
 
 pub fn process(input: &str) -> usize {
     let map = Map::new(input);
-    let start = map.get_tile(0, 1).unwrap();
-    let i_len = map.tiles.len();
-    let j_len = map.tiles[0].len();
-    let end = (i_len - 1, j_len - 2);
+    let (start, end) = map.start_and_end();
     let distance = map.walk_longest(start, end);
     distance
 }
@@ -1,19 +1,19 @@
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct Tile {
-    i: usize,
-    j: usize,
+pub(crate) struct Tile {
+    pub(crate) i: usize,
+    pub(crate) j: usize,
 }
 
-struct Map {
+pub(crate) struct Map {
     tiles: Vec<Vec<Option<Tile>>>,
-    intersections: Vec<Tile>,
+    pub(crate) intersections: Vec<Tile>,
     graph: HashMap<Tile, Vec<(Tile, usize)>>,
 }
 
 impl Map {
-    fn new(input: &str) -> Self {
+    pub(crate) fn new(input: &str) -> Self {
         let mut tiles = Vec::new();
         for (i, line) in input.lines().enumerate() {
             let mut row = Vec::new();
@@ -38,29 +38,36 @@ impl Map {
         map
     }
 
-    fn dfs(&self, start: Tile, end: Tile, seen: &mut HashSet<Tile>) -> Option<usize> {
+    fn dfs(
+        &self,
+        start: Tile,
+        end: Tile,
+        steps: usize,
+        seen: &mut HashSet<Tile>,
+        path: &mut Vec<Tile>,
+        longest: &mut Option<(usize, Vec<Tile>)>,
+    ) {
         if start == end {
-            return Some(0);
+            let is_longer = match longest {
+                Some((longest_steps, _)) => steps > *longest_steps,
+                None => true,
+            };
+            if is_longer {
+                *longest = Some((steps, path.clone()));
+            }
+            return;
         }
 
         seen.insert(start);
-        let mut max = None;
         let tile_graph = self.graph.get(&start).unwrap();
-        for (tile, steps) in tile_graph {
+        for (tile, tile_steps) in tile_graph {
             if !seen.contains(tile) {
-                let other_dfs = self.dfs(*tile, end, seen);
-                if other_dfs.is_none() {
-                    continue;
-                }
-                let other_dfs = other_dfs.unwrap();
-                let total = steps + other_dfs;
-                if max == None || total > max.unwrap() {
-                    max = Some(total);
-                }
+                path.push(*tile);
+                self.dfs(*tile, end, steps + tile_steps, seen, path, longest);
+                path.pop();
             }
         }
         seen.remove(&start);
-        max
     }
 
     fn graph_intersection(&self) -> HashMap<Tile, Vec<(Tile, usize)>> {
@@ -115,11 +122,26 @@ impl Map {
     }
 
     fn walk_longest(&self) -> usize {
+        let (distance, _) = self.longest_hike();
+        distance
+    }
+
+    //The length of the longest hike and the intersections it goes through, including the start and the end
+    pub(crate) fn longest_hike(&self) -> (usize, Vec<Tile>) {
         let start = self.get_tile(0, 1).unwrap();
         let end_i = (self.tiles.len() - 1) as isize;
         let end_j = (self.tiles[0].len() - 2) as isize;
         let end = self.get_tile(end_i, end_j).unwrap();
-        self.dfs(start, end, &mut HashSet::new()).unwrap()
+        let mut longest = None;
+        self.dfs(
+            start,
+            end,
+            0,
+            &mut HashSet::new(),
+            &mut vec![start],
+            &mut longest,
+        );
+        longest.unwrap()
     }
 
     fn get_tile(&self, i: isize, j: isize) -> Option<Tile> {
@@ -137,7 +159,7 @@ impl Map {
         *tile
     }
 
-    fn get_possible_tiles(&self, tile: Tile) -> Vec<Tile> {
+    pub(crate) fn get_possible_tiles(&self, tile: Tile) -> Vec<Tile> {
         let mut tiles = Vec::new();
         let i = tile.i;
         let j = tile.j;
@@ -0,0 +1,91 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{part_1, part_2};
+
+/// Steps of the longest hike down the icy slopes, and its tiles from the start
+pub fn longest_hike(input: &str) -> (usize, Vec<(usize, usize)>) {
+    let map = part_1::Map::new(input);
+    let (start, end) = map.start_and_end();
+    let hike: Vec<(usize, usize)> = map
+        .longest_hike(start, end)
+        .iter()
+        .map(|tile| (tile.i, tile.j))
+        .collect();
+    (hike.len() - 1, hike)
+}
+
+/// Steps of the longest hike when the slopes are dry, and its tiles from the start
+pub fn longest_dry_hike(input: &str) -> (usize, Vec<(usize, usize)>) {
+    let map = part_2::Map::new(input);
+    let (distance, intersections) = map.longest_hike();
+    let mut hike = vec![(intersections[0].i, intersections[0].j)];
+    for pair in intersections.windows(2) {
+        hike.extend(corridor(&map, pair[0], pair[1]));
+    }
+    (distance, hike)
+}
+
+//The tiles walked from one intersection to the next, without the first one
+fn corridor(map: &part_2::Map, from: part_2::Tile, to: part_2::Tile) -> Vec<(usize, usize)> {
+    let mut previous = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    seen.insert(from);
+    queue.push_back(from);
+    while let Some(tile) = queue.pop_front() {
+        if tile == to {
+            break;
+        }
+        //Other intersections lead to other corridors
+        if tile != from && map.intersections.contains(&tile) {
+            continue;
+        }
+        for option in map.get_possible_tiles(tile) {
+            if seen.insert(option) {
+                previous.insert(option, tile);
+                queue.push_back(option);
+            }
+        }
+    }
+
+    let mut corridor = Vec::new();
+    let mut tile = to;
+    while tile != from {
+        corridor.push((tile.i, tile.j));
+        tile = previous[&tile];
+    }
+    corridor.reverse();
+    corridor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_connected(hike: &[(usize, usize)]) {
+        for pair in hike.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert_eq!(a.0.abs_diff(b.0) + a.1.abs_diff(b.1), 1);
+        }
+    }
+
+    #[test]
+    fn test_longest_hike() {
+        let input = include_str!("./inputs/test.txt");
+        let (steps, hike) = longest_hike(input);
+        assert_eq!(steps, 94);
+        assert_eq!(hike.len() - 1, 94);
+        assert_connected(&hike);
+    }
+
+    #[test]
+    fn test_longest_dry_hike() {
+        let input = include_str!("./inputs/test.txt");
+        let (steps, hike) = longest_dry_hike(input);
+        assert_eq!(steps, 154);
+        assert_eq!(hike.first(), Some(&(0, 1)));
+        assert_eq!(hike.last(), Some(&(22, 21)));
+        assert_eq!(hike.len() - 1, 154);
+        assert_connected(&hike);
+    }
+}
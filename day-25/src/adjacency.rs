@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+/// The wiring diagram with every component numbered in the order it's found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adjacency {
+    labels: Vec<String>,
+    ids: HashMap<String, usize>,
+    neighbours: Vec<Vec<usize>>,
+    // every wire once, the lower id first
+    edges: Vec<(usize, usize)>,
+}
+
+impl Adjacency {
+    pub fn new(input: &str) -> Self {
+        let mut graph = Self::empty();
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (src, dst) = line.split_once(':').unwrap();
+            let src = graph.add_vertex(src.trim());
+            for label in dst.split_whitespace() {
+                let dst = graph.add_vertex(label);
+                graph.add_edge(src, dst);
+            }
+        }
+        graph
+    }
+
+    pub(crate) fn empty() -> Self {
+        Self {
+            labels: Vec::new(),
+            ids: HashMap::new(),
+            neighbours: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub(crate) fn add_vertex(&mut self, label: &str) -> usize {
+        if let Some(id) = self.ids.get(label) {
+            return *id;
+        }
+        self.labels.push(label.to_string());
+        self.ids.insert(label.to_string(), self.labels.len() - 1);
+        self.neighbours.push(Vec::new());
+        self.labels.len() - 1
+    }
+
+    pub(crate) fn add_edge(&mut self, src: usize, dst: usize) {
+        if src == dst || self.neighbours[src].contains(&dst) {
+            return;
+        }
+        self.neighbours[src].push(dst);
+        self.neighbours[dst].push(src);
+        self.edges.push((src.min(dst), src.max(dst)));
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn label(&self, id: usize) -> &str {
+        &self.labels[id]
+    }
+
+    pub fn id(&self, label: &str) -> Option<usize> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn neighbours(&self, id: usize) -> &[usize] {
+        &self.neighbours[id]
+    }
+
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let graph = Adjacency::new("a: b c\nb: a c d\n\n");
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.edges(), &[(0, 1), (0, 2), (1, 2), (1, 3)]);
+        assert_eq!(graph.neighbours(graph.id("b").unwrap()), &[0, 2, 3]);
+        assert_eq!(graph.label(3), "d");
+        assert_eq!(graph.id("e"), None);
+    }
+}
@@ -0,0 +1,34 @@
+use day_25_lib::karger::{Graph, Options, USAGE};
+
+// cargo run -p day-25 --bin karger -- path/to/input.txt --stein --seed 7
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (path, flags) = match args.split_first() {
+        Some((path, flags)) if !path.starts_with("--") => (path, flags),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+    };
+    let options = Options::parse(flags).unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        std::process::exit(1);
+    });
+    let input = std::fs::read_to_string(path).unwrap_or_else(|error| {
+        eprintln!("can't read {}: {}", path, error);
+        std::process::exit(1);
+    });
+
+    let graph = Graph::new(&input);
+    match graph.kager_min_cut(&options) {
+        Ok(run) => {
+            println!("Part 1: {}", run.graph.weight());
+            println!("{}", run.stats);
+        }
+        Err(stats) => {
+            eprintln!("no cut found");
+            eprintln!("{}", stats);
+            std::process::exit(1);
+        }
+    }
+}
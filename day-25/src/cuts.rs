@@ -0,0 +1,217 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+};
+
+use crate::{
+    adjacency::Adjacency,
+    min_cut::{self, Cut, Network},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CutError {
+    TooFewVertices(usize),
+    NoCut { wires: usize, minimum: usize },
+}
+
+impl Display for CutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CutError::TooFewVertices(count) => {
+                write!(f, "a cut needs two components, the diagram has {}", count)
+            }
+            CutError::NoCut { wires, minimum } => write!(
+                f,
+                "no cut of {} wires or less, the smallest one has {}",
+                wires, minimum
+            ),
+        }
+    }
+}
+
+/// Any cut of `wires` wires or less, found by the flows from the first vertex,
+/// which stop once they're past `wires`
+pub fn find_cut(graph: &Adjacency, wires: usize) -> Result<Cut, CutError> {
+    if graph.len() < 2 {
+        return Err(CutError::TooFewVertices(graph.len()));
+    }
+    let mut network = Network::new(graph);
+    for sink in 1..graph.len() {
+        if network.max_flow(0, sink, wires + 1) <= wires {
+            return Ok(Cut::from_side(graph, &network.source_side(0)));
+        }
+    }
+    let minimum = min_cut::stoer_wagner(graph).unwrap().edges.len();
+    Err(CutError::NoCut { wires, minimum })
+}
+
+/// Every cut with the fewest wires, sorted by their first group.
+/// A minimum cut separates the first vertex from some other one, and the minimum
+/// cuts between the two are the groups closed under the residual network of a maximum flow.
+pub fn minimum_cuts(graph: &Adjacency) -> Result<Vec<Cut>, CutError> {
+    let minimum = match min_cut::stoer_wagner(graph) {
+        Some(cut) => cut.edges.len(),
+        None => return Err(CutError::TooFewVertices(graph.len())),
+    };
+    let mut network = Network::new(graph);
+    let mut sides: HashSet<Vec<bool>> = HashSet::new();
+    for sink in 1..graph.len() {
+        if network.max_flow(0, sink, minimum + 1) > minimum {
+            continue;
+        }
+        let mut side = vec![None; graph.len()];
+        include(&network, &mut side, 0);
+        exclude(&network, &mut side, sink);
+        closed_sides(&network, side, &mut sides);
+    }
+    let mut cuts: Vec<Cut> = sides
+        .iter()
+        .map(|side| Cut::from_side(graph, side))
+        .collect();
+    cuts.sort_by(|a, b| a.sides.cmp(&b.sides));
+    Ok(cuts)
+}
+
+// Puts `id` in the first group, with every vertex it can send a unit to
+fn include(network: &Network, side: &mut [Option<bool>], id: usize) {
+    let mut stack = vec![id];
+    side[id] = Some(true);
+    while let Some(id) = stack.pop() {
+        for to in network.forward(id) {
+            if side[to].is_none() {
+                side[to] = Some(true);
+                stack.push(to);
+            }
+        }
+    }
+}
+
+// Puts `id` in the second group, with every vertex that can send a unit to it
+fn exclude(network: &Network, side: &mut [Option<bool>], id: usize) {
+    let mut stack = vec![id];
+    side[id] = Some(false);
+    while let Some(id) = stack.pop() {
+        for from in network.backward(id) {
+            if side[from].is_none() {
+                side[from] = Some(false);
+                stack.push(from);
+            }
+        }
+    }
+}
+
+// Every way to place the undecided vertices, both choices always leave a closed group
+fn closed_sides(network: &Network, side: Vec<Option<bool>>, found: &mut HashSet<Vec<bool>>) {
+    let undecided = match side.iter().position(|placed| placed.is_none()) {
+        Some(id) => id,
+        None => {
+            found.insert(side.into_iter().map(|placed| placed.unwrap()).collect());
+            return;
+        }
+    };
+    let mut included = side.clone();
+    include(network, &mut included, undecided);
+    closed_sides(network, included, found);
+    let mut excluded = side;
+    exclude(network, &mut excluded, undecided);
+    closed_sides(network, excluded, found);
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::fixtures::EXAMPLE;
+
+    #[test]
+    fn test_find_cut() {
+        let graph = Adjacency::new(EXAMPLE);
+        let cut = find_cut(&graph, 3).unwrap();
+        assert_eq!(cut.edges.len(), 3);
+        assert_eq!(cut.sizes(), [6, 9]);
+        assert_eq!(cut.weight(), 54);
+        assert!(find_cut(&graph, 10).unwrap().edges.len() <= 10);
+        assert_eq!(
+            find_cut(&graph, 2),
+            Err(CutError::NoCut {
+                wires: 2,
+                minimum: 3
+            })
+        );
+        assert_eq!(
+            find_cut(&graph, 2).unwrap_err().to_string(),
+            "no cut of 2 wires or less, the smallest one has 3"
+        );
+        assert_eq!(
+            find_cut(&Adjacency::new("a: b"), 0),
+            Err(CutError::NoCut {
+                wires: 0,
+                minimum: 1
+            })
+        );
+        assert_eq!(
+            find_cut(&Adjacency::new(""), 3),
+            Err(CutError::TooFewVertices(0))
+        );
+    }
+
+    #[test]
+    fn test_minimum_cuts() {
+        let cuts = minimum_cuts(&Adjacency::new(EXAMPLE)).unwrap();
+        assert_eq!(cuts.len(), 1);
+        assert_eq!(cuts[0].weight(), 54);
+
+        // every pair of wires of a cycle
+        let cycle = Adjacency::new("a: b\nb: c\nc: d\nd: e\ne: a");
+        let cuts = minimum_cuts(&cycle).unwrap();
+        assert_eq!(cuts.len(), 10);
+        assert!(cuts.iter().all(|cut| cut.edges.len() == 2));
+        assert_eq!(cuts[0].sides, [vec!["a"], vec!["b", "c", "d", "e"]]);
+
+        // a path of four wires, each one is a cut
+        let path = Adjacency::new("a: b\nb: c\nc: d\nd: e");
+        let cuts = minimum_cuts(&path).unwrap();
+        let sizes: Vec<[usize; 2]> = cuts.iter().map(|cut| cut.sizes()).collect();
+        assert_eq!(sizes, vec![[1, 4], [2, 3], [3, 2], [4, 1]]);
+    }
+
+    #[test]
+    fn test_minimum_cuts_of_disconnected_graph() {
+        let cuts = minimum_cuts(&Adjacency::new("a: b\nc: d")).unwrap();
+        assert_eq!(cuts.len(), 1);
+        assert!(cuts[0].edges.is_empty());
+        assert_eq!(cuts[0].sides, [vec!["a", "b"], vec!["c", "d"]]);
+    }
+
+    #[test]
+    fn test_minimum_cuts_of_random_graphs() {
+        let mut random = StdRng::seed_from_u64(45);
+        for _ in 0..200 {
+            let n = random.gen_range(2..9);
+            let lines: Vec<String> = (0..n)
+                .map(|id| {
+                    let neighbours: Vec<String> = (0..n)
+                        .filter(|other| *other != id && random.gen_bool(0.35))
+                        .map(|other| format!("v{}", other))
+                        .collect();
+                    format!("v{}: {}", id, neighbours.join(" "))
+                })
+                .collect();
+            let graph = Adjacency::new(&lines.join("\n"));
+            let n = graph.len();
+            // every split once, the last vertex always in the second group
+            let sizes: Vec<usize> = (1..1usize << (n - 1))
+                .map(|mask| {
+                    let side: Vec<bool> = (0..n).map(|id| mask & (1 << id) != 0).collect();
+                    Cut::from_side(&graph, &side).edges.len()
+                })
+                .collect();
+            let minimum = *sizes.iter().min().unwrap();
+            let expected = sizes.iter().filter(|size| **size == minimum).count();
+            let cuts = minimum_cuts(&graph).unwrap();
+            assert_eq!(cuts.len(), expected, "{}", lines.join("\n"));
+            assert!(cuts.iter().all(|cut| cut.edges.len() == minimum));
+        }
+    }
+}
@@ -0,0 +1,16 @@
+// The puzzle's example, shared by the tests
+
+/// Cut `hfx/pzl`, `bvb/cmg` and `nvd/jqt` to get two groups of 9 and 6 components
+pub const EXAMPLE: &str = "jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
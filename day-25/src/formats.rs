@@ -0,0 +1,542 @@
+use std::io::{self, Write};
+
+use crate::{adjacency::Adjacency, min_cut::Cut};
+
+// fill colours of the cut's groups, and of its wires
+const SIDE_COLORS: [&str; 2] = ["lightblue", "orange"];
+const CUT_COLOR: &str = "red";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    // undirected Graphviz graph
+    Dot,
+    // a wire per line, `source,target`
+    Csv,
+    GraphMl,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name.to_ascii_lowercase().as_str() {
+            "dot" | "gv" => Some(Format::Dot),
+            "csv" => Some(Format::Csv),
+            "graphml" => Some(Format::GraphMl),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the diagram, with the groups of `cut` coloured and its wires highlighted
+pub fn write<W: Write>(
+    graph: &Adjacency,
+    cut: Option<&Cut>,
+    format: Format,
+    writer: &mut W,
+) -> io::Result<()> {
+    let marks = Marks::new(graph, cut);
+    match format {
+        Format::Dot => write_dot(graph, &marks, writer),
+        Format::Csv => write_csv(graph, &marks, writer),
+        Format::GraphMl => write_graphml(graph, &marks, writer),
+    }
+}
+
+/// Reads a diagram written in `format`, the colours and the cut are ignored
+pub fn read(input: &str, format: Format) -> Result<Adjacency, String> {
+    match format {
+        Format::Dot => read_dot(input),
+        Format::Csv => read_csv(input),
+        Format::GraphMl => read_graphml(input),
+    }
+}
+
+// The group of every vertex and whether every wire is cut, when there's a cut
+struct Marks {
+    sides: Option<Vec<usize>>,
+    cut: Vec<bool>,
+}
+
+impl Marks {
+    fn new(graph: &Adjacency, cut: Option<&Cut>) -> Self {
+        let cut = match cut {
+            Some(cut) => cut,
+            None => {
+                return Marks {
+                    sides: None,
+                    cut: vec![false; graph.edges().len()],
+                }
+            }
+        };
+        let mut sides = vec![0; graph.len()];
+        for label in &cut.sides[1] {
+            if let Some(id) = graph.id(label) {
+                sides[id] = 1;
+            }
+        }
+        let cut = graph
+            .edges()
+            .iter()
+            .map(|(a, b)| sides[*a] != sides[*b])
+            .collect();
+        Marks {
+            sides: Some(sides),
+            cut,
+        }
+    }
+}
+
+fn write_dot<W: Write>(graph: &Adjacency, marks: &Marks, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "graph G {{")?;
+    for id in 0..graph.len() {
+        let label = dot_id(graph.label(id));
+        match &marks.sides {
+            Some(sides) => writeln!(
+                writer,
+                "    {} [style=filled, fillcolor={}];",
+                label, SIDE_COLORS[sides[id]]
+            )?,
+            None => writeln!(writer, "    {};", label)?,
+        }
+    }
+    for (i, (a, b)) in graph.edges().iter().enumerate() {
+        let (a, b) = (dot_id(graph.label(*a)), dot_id(graph.label(*b)));
+        match marks.cut[i] {
+            true => writeln!(
+                writer,
+                "    {} -- {} [color={}, penwidth=3];",
+                a, b, CUT_COLOR
+            )?,
+            false => writeln!(writer, "    {} -- {};", a, b)?,
+        }
+    }
+    writeln!(writer, "}}")
+}
+
+fn dot_id(label: &str) -> String {
+    format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_csv<W: Write>(graph: &Adjacency, marks: &Marks, writer: &mut W) -> io::Result<()> {
+    match &marks.sides {
+        Some(_) => writeln!(writer, "source,target,source_side,target_side,cut")?,
+        None => writeln!(writer, "source,target")?,
+    }
+    for (i, (a, b)) in graph.edges().iter().enumerate() {
+        let (a_label, b_label) = (csv_field(graph.label(*a)), csv_field(graph.label(*b)));
+        write!(writer, "{},{}", a_label, b_label)?;
+        if let Some(sides) = &marks.sides {
+            write!(writer, ",{},{},{}", sides[*a], sides[*b], marks.cut[i])?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+// Quoted when the reader would split or trim the label, a quote is written twice
+fn csv_field(label: &str) -> String {
+    match label.contains([',', '"']) || label.trim() != label {
+        true => format!("\"{}\"", label.replace('"', "\"\"")),
+        false => label.to_string(),
+    }
+}
+
+fn write_graphml<W: Write>(graph: &Adjacency, marks: &Marks, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        writer,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+    )?;
+    writeln!(
+        writer,
+        "  <key id=\"side\" for=\"node\" attr.name=\"side\" attr.type=\"int\"/>"
+    )?;
+    writeln!(
+        writer,
+        "  <key id=\"cut\" for=\"edge\" attr.name=\"cut\" attr.type=\"boolean\"/>"
+    )?;
+    writeln!(
+        writer,
+        "  <key id=\"color\" for=\"all\" attr.name=\"color\" attr.type=\"string\"/>"
+    )?;
+    writeln!(writer, "  <graph id=\"G\" edgedefault=\"undirected\">")?;
+    for id in 0..graph.len() {
+        let label = xml_escape(graph.label(id));
+        match &marks.sides {
+            Some(sides) => writeln!(
+                writer,
+                "    <node id=\"{}\"><data key=\"side\">{}</data><data key=\"color\">{}</data></node>",
+                label, sides[id], SIDE_COLORS[sides[id]]
+            )?,
+            None => writeln!(writer, "    <node id=\"{}\"/>", label)?,
+        }
+    }
+    for (i, (a, b)) in graph.edges().iter().enumerate() {
+        let (a, b) = (xml_escape(graph.label(*a)), xml_escape(graph.label(*b)));
+        match (&marks.sides, marks.cut[i]) {
+            (None, _) => writeln!(writer, "    <edge source=\"{}\" target=\"{}\"/>", a, b)?,
+            (Some(_), true) => writeln!(
+                writer,
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"cut\">true</data><data key=\"color\">{}</data></edge>",
+                a, b, CUT_COLOR
+            )?,
+            (Some(_), false) => writeln!(
+                writer,
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"cut\">false</data></edge>",
+                a, b
+            )?,
+        }
+    }
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+// Statements like `a -- b -- c [color=red];` or `a [shape=box];`, one per line
+fn read_dot(input: &str) -> Result<Adjacency, String> {
+    let mut graph = Adjacency::empty();
+    let statements = dot_statements(input);
+    // the header, like `strict graph G`, is everything before the first `{`
+    let body = statements.iter().position(|(_, end)| *end == '{');
+    let header = statements[..body.map_or(0, |body| body + 1)]
+        .iter()
+        .flat_map(|(statement, _)| statement.split_whitespace())
+        .find(|word| *word != "strict");
+    let body = match (header, body) {
+        (Some("graph" | "digraph"), Some(body)) => body + 1,
+        _ => return Err("expected a `graph` or a `digraph`".to_string()),
+    };
+    for (statement, _) in &statements[body..] {
+        // only the header of a subgraph is skipped, its statements are read like the others
+        if statement.is_empty() || statement.split_whitespace().next() == Some("subgraph") {
+            continue;
+        }
+        let ids = match dot_statement(statement) {
+            Some(ids) => ids,
+            None => continue,
+        };
+        if let [id] = ids[..] {
+            if matches!(id, "node" | "edge" | "graph") {
+                continue;
+            }
+        }
+        let ids: Vec<String> = ids.into_iter().map(dot_unquote).collect();
+        if ids.iter().any(|id| id.is_empty()) {
+            return Err(format!("can't read `{}`", statement));
+        }
+        let ids: Vec<usize> = ids.iter().map(|id| graph.add_vertex(id)).collect();
+        for pair in ids.windows(2) {
+            graph.add_edge(pair[0], pair[1]);
+        }
+    }
+    Ok(graph)
+}
+
+// The statements, split on `;`, the braces and the line ends outside the quotes
+// and the attribute lists, each with the character that ends it
+fn dot_statements(input: &str) -> Vec<(&str, char)> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut attributes = false;
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quoted => {
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            _ if quoted => {}
+            '[' => attributes = true,
+            ']' => attributes = false,
+            _ if attributes => {}
+            ';' | '{' | '}' | '\n' => {
+                statements.push((input[start..i].trim(), c));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push((input[start..].trim(), '\n'));
+    statements
+}
+
+// The ids of a statement like `"a" -- b [color=red];`, still quoted.
+// The attributes, the `;` and the edges are only looked for outside the quotes,
+// `None` for a graph attribute like `rankdir=LR`.
+fn dot_statement(line: &str) -> Option<Vec<&str>> {
+    let mut ids = Vec::new();
+    let mut start = 0;
+    let mut end = line.len();
+    let mut quoted = false;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quoted => {
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            _ if quoted => {}
+            '[' | ';' => {
+                end = i;
+                break;
+            }
+            '=' => return None,
+            '-' if matches!(chars.peek(), Some((_, '-' | '>'))) => {
+                chars.next();
+                ids.push(line[start..i].trim());
+                start = i + 2;
+            }
+            _ => {}
+        }
+    }
+    ids.push(line[start..end].trim());
+    Some(ids)
+}
+
+fn dot_unquote(id: &str) -> String {
+    match id.strip_prefix('"').and_then(|id| id.strip_suffix('"')) {
+        Some(id) => id.replace("\\\"", "\"").replace("\\\\", "\\"),
+        None => id.to_string(),
+    }
+}
+
+fn read_csv(input: &str) -> Result<Adjacency, String> {
+    let mut graph = Adjacency::empty();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (i == 0 && line.starts_with("source,")) {
+            continue;
+        }
+        let fields = csv_fields(line).map_err(|error| format!("line {}: {}", i + 1, error))?;
+        match &fields[..] {
+            [source, target, ..] if !source.is_empty() && !target.is_empty() => {
+                let (source, target) = (graph.add_vertex(source), graph.add_vertex(target));
+                graph.add_edge(source, target);
+            }
+            _ => return Err(format!("line {}: expected `source,target`", i + 1)),
+        }
+    }
+    Ok(graph)
+}
+
+// The fields of the line, trimmed unless they're quoted.
+// A quoted field can hold `,` and `""` for a quote.
+fn csv_fields(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let (mut quoted, mut was_quoted) = (false, false);
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if !was_quoted && field.trim().is_empty() => {
+                field.clear();
+                (quoted, was_quoted) = (true, true);
+            }
+            (false, ',') => {
+                fields.push(csv_field_value(field, was_quoted));
+                field = String::new();
+                was_quoted = false;
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("a quoted field isn't closed".to_string());
+    }
+    fields.push(csv_field_value(field, was_quoted));
+    Ok(fields)
+}
+
+fn csv_field_value(field: String, quoted: bool) -> String {
+    match quoted {
+        true => field,
+        false => field.trim().to_string(),
+    }
+}
+
+// Only the `node` and `edge` elements, the rest of the document is skipped
+fn read_graphml(input: &str) -> Result<Adjacency, String> {
+    let mut graph = Adjacency::empty();
+    for element in input.split('<').skip(1) {
+        if let Some(node) = element.strip_prefix("node ") {
+            let id = attribute(node, "id").ok_or("a node without an id")?;
+            graph.add_vertex(&id);
+        } else if let Some(edge) = element.strip_prefix("edge ") {
+            let source = attribute(edge, "source").ok_or("an edge without a source")?;
+            let target = attribute(edge, "target").ok_or("an edge without a target")?;
+            let (source, target) = (graph.add_vertex(&source), graph.add_vertex(&target));
+            graph.add_edge(source, target);
+        }
+    }
+    Ok(graph)
+}
+
+// The value of `name="..."`, the name starting the element or after a space
+fn attribute(element: &str, name: &str) -> Option<String> {
+    let pattern = format!("{}=\"", name);
+    let mut from = 0;
+    loop {
+        let start = from + element[from..].find(&pattern)?;
+        let value = &element[start + pattern.len()..];
+        if start == 0 || element[..start].ends_with(char::is_whitespace) {
+            let end = value.find('"')?;
+            return Some(xml_unescape(&value[..end]));
+        }
+        from = start + pattern.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::EXAMPLE, min_cut};
+
+    fn written(graph: &Adjacency, cut: Option<&Cut>, format: Format) -> String {
+        let mut buffer = Vec::new();
+        write(graph, cut, format, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_write_dot() {
+        let graph = Adjacency::new("a: b\nb: c");
+        assert_eq!(
+            written(&graph, None, Format::Dot),
+            "graph G {\n    \"a\";\n    \"b\";\n    \"c\";\n    \"a\" -- \"b\";\n    \"b\" -- \"c\";\n}\n"
+        );
+        let cut = Cut::from_side(&graph, &[true, true, false]);
+        assert_eq!(
+            written(&graph, Some(&cut), Format::Dot),
+            "graph G {\n    \
+             \"a\" [style=filled, fillcolor=lightblue];\n    \
+             \"b\" [style=filled, fillcolor=lightblue];\n    \
+             \"c\" [style=filled, fillcolor=orange];\n    \
+             \"a\" -- \"b\";\n    \
+             \"b\" -- \"c\" [color=red, penwidth=3];\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let graph = Adjacency::new("a: b\nb: c");
+        assert_eq!(
+            written(&graph, None, Format::Csv),
+            "source,target\na,b\nb,c\n"
+        );
+        let cut = Cut::from_side(&graph, &[true, false, false]);
+        assert_eq!(
+            written(&graph, Some(&cut), Format::Csv),
+            "source,target,source_side,target_side,cut\na,b,0,1,true\nb,c,1,1,false\n"
+        );
+    }
+
+    #[test]
+    fn test_write_graphml() {
+        let graph = Adjacency::new("a: b");
+        let cut = Cut::from_side(&graph, &[true, false]);
+        let graphml = written(&graph, Some(&cut), Format::GraphMl);
+        assert!(graphml.contains(
+            "<node id=\"b\"><data key=\"side\">1</data><data key=\"color\">orange</data></node>"
+        ));
+        assert!(graphml.contains(
+            "<edge source=\"a\" target=\"b\"><data key=\"cut\">true</data><data key=\"color\">red</data></edge>"
+        ));
+        assert!(
+            written(&graph, None, Format::GraphMl).contains("<edge source=\"a\" target=\"b\"/>")
+        );
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let graph = Adjacency::new(EXAMPLE);
+        let cut = min_cut::stoer_wagner(&graph).unwrap();
+        for format in [Format::Dot, Format::Csv, Format::GraphMl] {
+            for cut in [None, Some(&cut)] {
+                let read = read(&written(&graph, cut, format), format).unwrap();
+                assert_eq!(read, graph, "{:?}", format);
+            }
+        }
+        let dot = written(&graph, Some(&cut), Format::Dot);
+        assert_eq!(dot.matches("color=red").count(), 3);
+        assert_eq!(dot.matches("fillcolor=lightblue").count(), 6);
+    }
+
+    #[test]
+    fn test_escaped_labels() {
+        let mut graph = Adjacency::empty();
+        let a = graph.add_vertex("a \"quoted\" <label> & more");
+        let b = graph.add_vertex("b");
+        graph.add_edge(a, b);
+        let c = graph.add_vertex("c, [d=e]; f -- g");
+        let h = graph.add_vertex(" h ");
+        graph.add_edge(c, h);
+        graph.add_edge(c, a);
+        for format in [Format::Dot, Format::Csv, Format::GraphMl] {
+            assert_eq!(
+                read(&written(&graph, None, format), format),
+                Ok(graph.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_dot_statements() {
+        let edges = |dot: &str| {
+            let graph = read(dot, Format::Dot).unwrap();
+            (graph.len(), graph.edges().len())
+        };
+        assert_eq!(edges("graph { a -- b }"), (2, 1));
+        assert_eq!(edges("graph {\n  a -- b; c -- d;\n}"), (4, 2));
+        assert_eq!(edges("strict graph\nG\n{ a -- b; b -- c }"), (3, 2));
+        assert_eq!(
+            edges(
+                "graph {\n  subgraph cluster_x {\n    a -- b\n  }\n  subgraph { c }\n  b -- c\n}"
+            ),
+            (3, 2)
+        );
+        assert_eq!(
+            edges("graph {\n  a -- b [color=red; style=\"a; b\"]\n}"),
+            (2, 1)
+        );
+    }
+
+    #[test]
+    fn test_read_other_tools() {
+        let dot = "digraph {\n  node [shape=box];\n  a -> b -> c;\n  d;\n}";
+        let graph = read(dot, Format::Dot).unwrap();
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.edges().len(), 2);
+        assert!(read("a -- b", Format::Dot).is_err());
+        assert!(read("a { b }", Format::Dot).is_err());
+        assert_eq!(
+            read("a,b\nc", Format::Csv),
+            Err("line 2: expected `source,target`".to_string())
+        );
+        assert_eq!(
+            read("\"a,b", Format::Csv),
+            Err("line 1: a quoted field isn't closed".to_string())
+        );
+        assert_eq!(Format::parse("GraphML"), Some(Format::GraphMl));
+        assert_eq!(Format::parse("svg"), None);
+    }
+}
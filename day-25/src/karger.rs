@@ -0,0 +1,561 @@
+use std::{
+    collections::BTreeMap,
+    f64::consts::SQRT_2,
+    fmt::{self, Display},
+    io::{self, Write},
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{
+    adjacency::Adjacency,
+    formats::{self, Format},
+    min_cut::Cut,
+    union_find::UnionFind,
+};
+
+pub const USAGE: &str = "\
+usage: karger <file> [options]
+  --stein          Karger-Stein recursive contractions instead of plain ones
+  --seed N         seed of the random generator, 0 by default
+  --trials N       most trials to run, 10000 by default
+  --wires N        stops at the first cut of N wires or less, 3 by default
+  --exhaustive     runs every trial and keeps the smallest cut, the only way
+                   to estimate how often a trial finds it";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Karger,
+    KargerStein,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    pub algorithm: Algorithm,
+    pub seed: u64,
+    pub trials: usize,
+    // stops at the first cut of this many wires or less, every trial runs without it
+    pub target: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            algorithm: Algorithm::Karger,
+            seed: 0,
+            trials: 10_000,
+            target: Some(3),
+        }
+    }
+}
+
+impl Options {
+    /// Reads the options of `USAGE`, the others are errors
+    pub fn parse(args: &[String]) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| -> Result<u64, String> {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("`{}` needs a value", name))?;
+                value
+                    .parse()
+                    .map_err(|_| format!("`{}` is not a number", value))
+            };
+            match arg.as_str() {
+                "--stein" => options.algorithm = Algorithm::KargerStein,
+                "--seed" => options.seed = value(arg)?,
+                "--trials" => options.trials = value(arg)? as usize,
+                "--wires" => options.target = Some(value(arg)? as usize),
+                "--exhaustive" => options.target = None,
+                arg => return Err(format!("unknown option `{}`", arg)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// What the trials found
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub trials: usize,
+    // by cut size, how many trials found a cut of that size
+    pub cut_sizes: BTreeMap<usize, usize>,
+    // the trials stopped at the first cut as small as the target
+    pub stopped: bool,
+}
+
+impl Stats {
+    pub fn smallest(&self) -> Option<usize> {
+        self.cut_sizes.keys().next().copied()
+    }
+
+    /// Share of the trials that found the smallest cut seen, an estimate of
+    /// the chance a single trial finds it. `None` when the trials stopped at the
+    /// first small cut, which would always find it once.
+    pub fn success_probability(&self) -> Option<f64> {
+        if self.stopped {
+            return None;
+        }
+        match self.cut_sizes.values().next() {
+            Some(count) => Some(*count as f64 / self.trials as f64),
+            None => Some(0.0),
+        }
+    }
+
+    /// Chance that `trials` trials find the smallest cut at least once, by the estimate
+    pub fn confidence(&self, trials: usize) -> Option<f64> {
+        self.success_probability()
+            .map(|probability| 1.0 - (1.0 - probability).powi(trials as i32))
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "trials: {}", self.trials)?;
+        for (size, count) in &self.cut_sizes {
+            writeln!(f, "cuts of {} wires: {}", size, count)?;
+        }
+        match self.success_probability() {
+            Some(probability) => write!(f, "success probability: {:.2}%", probability * 100.0),
+            None => write!(
+                f,
+                "success probability: unknown, the trials stopped at the first cut, see --exhaustive"
+            ),
+        }
+    }
+}
+
+/// The smallest cut the trials found, with their statistics
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub graph: Graph,
+    pub stats: Stats,
+}
+
+/// The diagram being contracted: the wires between interned ids and the groups
+/// of vertices merged so far
+#[derive(Debug, Clone)]
+pub struct Graph {
+    adjacency: Adjacency,
+    groups: UnionFind,
+}
+
+impl Graph {
+    pub fn new(input: &str) -> Self {
+        let adjacency = Adjacency::new(input);
+        let groups = UnionFind::new(adjacency.len());
+        Self { adjacency, groups }
+    }
+
+    /// The product of the groups' sizes
+    pub fn weight(&self) -> usize {
+        self.component_sizes().iter().product()
+    }
+
+    /// The sizes of the groups, largest first
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self
+            .groups
+            .roots()
+            .map(|root| self.groups.size(root))
+            .collect();
+        sizes.sort_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// How many groups are left
+    pub fn vertex_count(&self) -> usize {
+        self.groups.count()
+    }
+
+    /// The wires between two groups
+    pub fn crossing_edges(&self) -> Vec<(usize, usize)> {
+        self.adjacency
+            .edges()
+            .iter()
+            .filter(|(a, b)| self.groups.find(*a) != self.groups.find(*b))
+            .copied()
+            .collect()
+    }
+
+    /// Merges the groups at both ends of the wire, false if it's inside a group already
+    pub fn collapse_vertex(&mut self, edge_i: usize) -> bool {
+        let (a, b) = self.adjacency.edges()[edge_i];
+        self.groups.union(a, b)
+    }
+
+    /// The cut between the group of the first vertex and the others
+    pub fn cut(&self) -> Cut {
+        let first = self.groups.find(0);
+        let side: Vec<bool> = (0..self.adjacency.len())
+            .map(|id| self.groups.find(id) == first)
+            .collect();
+        Cut::from_side(&self.adjacency, &side)
+    }
+
+    // Contracting the wires in a random order is contracting a random wire left every time
+    fn contract(&self, rng: &mut StdRng) -> Self {
+        let mut graph = self.clone();
+        let mut order: Vec<usize> = (0..self.adjacency.edges().len()).collect();
+        order.shuffle(rng);
+        for edge_i in order {
+            if graph.vertex_count() <= 2 {
+                break;
+            }
+            graph.collapse_vertex(edge_i);
+        }
+        graph
+    }
+
+    // The groups left once the wires of the cut are removed
+    fn without(&self, cut: &[usize]) -> Self {
+        let mut graph = self.clone();
+        let mut removed = vec![false; self.adjacency.edges().len()];
+        for wire in cut {
+            removed[*wire] = true;
+        }
+        for edge_i in (0..removed.len()).filter(|edge_i| !removed[*edge_i]) {
+            graph.collapse_vertex(edge_i);
+        }
+        graph
+    }
+
+    /// Runs the trials of `options`, the same seed gives the same run.
+    /// Fails with the statistics when no trial finds a cut as small as the target.
+    pub fn kager_min_cut(&self, options: &Options) -> Result<Run, Stats> {
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let multigraph = Multigraph::new(&self.adjacency);
+        let mut stats = Stats::default();
+        let mut best: Option<(usize, Graph)> = None;
+        for _ in 0..options.trials {
+            let graph = match options.algorithm {
+                Algorithm::Karger => self.contract(&mut rng),
+                Algorithm::KargerStein => self.without(&karger_stein(&multigraph, &mut rng)),
+            };
+            let size = graph.crossing_edges().len();
+            stats.trials += 1;
+            *stats.cut_sizes.entry(size).or_insert(0) += 1;
+            if best.as_ref().is_none_or(|(best, _)| size < *best) {
+                best = Some((size, graph));
+            }
+            if options.target.is_some_and(|target| size <= target) {
+                stats.stopped = true;
+                break;
+            }
+        }
+        match best {
+            Some((size, graph)) if options.target.is_none_or(|target| size <= target) => {
+                Ok(Run { graph, stats })
+            }
+            _ => Err(stats),
+        }
+    }
+
+    /// Writes the diagram, with the cut coloured once two groups are left
+    pub fn write<W: Write>(&self, format: Format, writer: &mut W) -> io::Result<()> {
+        let cut = (self.vertex_count() == 2).then(|| self.cut());
+        formats::write(&self.adjacency, cut.as_ref(), format, writer)
+    }
+}
+
+// The contracted diagram Karger–Stein recurses on: the groups renumbered and
+// only the wires between them left, by their index in the diagram
+#[derive(Debug, Clone)]
+struct Multigraph {
+    vertices: usize,
+    wires: Vec<(usize, usize, usize)>,
+}
+
+impl Multigraph {
+    fn new(adjacency: &Adjacency) -> Self {
+        let wires = adjacency
+            .edges()
+            .iter()
+            .enumerate()
+            .map(|(wire, (a, b))| (*a, *b, wire))
+            .collect();
+        Multigraph {
+            vertices: adjacency.len(),
+            wires,
+        }
+    }
+
+    // Contracts random wires until `target` groups are left, or no wire is
+    fn contract(&self, target: usize, rng: &mut StdRng) -> Self {
+        let mut groups = UnionFind::new(self.vertices);
+        let mut order: Vec<usize> = (0..self.wires.len()).collect();
+        order.shuffle(rng);
+        for i in order {
+            if groups.count() <= target {
+                break;
+            }
+            let (a, b, _) = self.wires[i];
+            groups.union(a, b);
+        }
+
+        let mut ids = vec![usize::MAX; self.vertices];
+        let mut vertices = 0;
+        for root in groups.roots() {
+            ids[root] = vertices;
+            vertices += 1;
+        }
+        let wires = self
+            .wires
+            .iter()
+            .map(|(a, b, wire)| (ids[groups.find(*a)], ids[groups.find(*b)], *wire))
+            .filter(|(a, b, _)| a != b)
+            .collect();
+        Multigraph { vertices, wires }
+    }
+}
+
+// The wires of a cut: contracts two copies down to 1 + n / √2 groups and
+// recurses on both, keeping the smaller cut
+fn karger_stein(graph: &Multigraph, rng: &mut StdRng) -> Vec<usize> {
+    if graph.wires.is_empty() {
+        return Vec::new();
+    }
+    if graph.vertices <= 6 {
+        let cut = graph.contract(2, rng);
+        return cut.wires.iter().map(|(_, _, wire)| *wire).collect();
+    }
+    let target = (1.0 + graph.vertices as f64 / SQRT_2).ceil() as usize;
+    let first = karger_stein(&graph.contract(target, rng), rng);
+    let second = karger_stein(&graph.contract(target, rng), rng);
+    match first.len() <= second.len() {
+        true => first,
+        false => second,
+    }
+}
+
+/// The puzzle's answer from the first cut of three wires random contractions find
+pub fn process(input: &str) -> usize {
+    let graph = Graph::new(input);
+    let run = graph
+        .kager_min_cut(&Options::default())
+        .unwrap_or_else(|stats| panic!("No min cut found in {} trials", stats.trials));
+    run.graph.weight()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::EXAMPLE;
+
+    #[test]
+    fn test_new() {
+        let input = include_str!("./inputs/test.txt");
+        let graph = Graph::new(input);
+        let mut dot = Vec::new();
+        graph.write(Format::Dot, &mut dot).unwrap();
+        assert!(dot.starts_with(b"graph G {"));
+    }
+
+    #[test]
+    fn test_new_input() {
+        let input = include_str!("./inputs/input.txt");
+        let graph = Graph::new(input);
+        let mut dot = Vec::new();
+        graph.write(Format::Dot, &mut dot).unwrap();
+        assert!(dot.starts_with(b"graph G {"));
+    }
+
+    #[test]
+    fn test_collapse_vertex() {
+        let input = include_str!("./inputs/test.txt");
+        let mut graph = Graph::new(input);
+        let count = graph.vertex_count();
+        for edge_i in 0..3 {
+            graph.collapse_vertex(edge_i);
+            assert_eq!(graph.vertex_count(), count - edge_i - 1);
+            graph.write(Format::Dot, &mut Vec::new()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_karger_min_cut() {
+        let input = include_str!("./inputs/test.txt");
+        let graph = Graph::new(input);
+        let min_cut = graph.kager_min_cut(&Options::default()).unwrap().graph;
+        let mut dot = Vec::new();
+        min_cut.write(Format::Dot, &mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert_eq!(dot.matches("color=red").count(), 3);
+    }
+
+    #[test]
+    fn test_weigth() {
+        let input = include_str!("./inputs/test.txt");
+        let graph = Graph::new(input);
+        let min_cut = graph.kager_min_cut(&Options::default()).unwrap().graph;
+        let weigth = min_cut.weight();
+        assert_eq!(weigth, 54);
+    }
+
+    #[test]
+    fn test_collapse_keeps_sizes() {
+        let mut graph = Graph::new("a: b c\nb: c\nc: d");
+        assert!(graph.collapse_vertex(0));
+        assert!(!graph.collapse_vertex(0));
+        assert_eq!(graph.component_sizes(), vec![2, 1, 1]);
+        assert!(graph.collapse_vertex(2));
+        assert_eq!(graph.component_sizes(), vec![3, 1]);
+        assert_eq!(graph.crossing_edges().len(), 1);
+        assert_eq!(graph.weight(), 3);
+    }
+
+    #[test]
+    fn test_labels_sharing_prefixes() {
+        // every label of the first group is part of the others, two groups of five
+        // joined by three wires
+        let input = "a: aa aaa aaaa aaaaa b
+            aa: aaa aaaa aaaaa ab
+            aaa: aaaa aaaaa aab
+            aaaa: aaaaa
+            b: ab aab aaab aaaab
+            ab: aab aaab aaaab
+            aab: aaab aaaab
+            aaab: aaaab";
+        let graph = Graph::new(input);
+        let min_cut = graph.kager_min_cut(&Options::default()).unwrap().graph;
+        assert_eq!(min_cut.component_sizes(), vec![5, 5]);
+        assert_eq!(min_cut.weight(), 25);
+        let cut = min_cut.cut();
+        assert_eq!(cut.sides[0], vec!["a", "aa", "aaa", "aaaa", "aaaaa"]);
+        assert_eq!(
+            cut.edges,
+            vec![
+                ("a".to_string(), "b".to_string()),
+                ("aa".to_string(), "ab".to_string()),
+                ("aaa".to_string(), "aab".to_string()),
+            ]
+        );
+    }
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_seeded_runs_repeat() {
+        let graph = Graph::new(EXAMPLE);
+        let options = Options {
+            seed: 47,
+            target: None,
+            trials: 50,
+            ..Options::default()
+        };
+        let first = graph.kager_min_cut(&options).unwrap();
+        let second = graph.kager_min_cut(&options).unwrap();
+        assert_eq!(first.stats, second.stats);
+        assert_eq!(first.stats.trials, 50);
+        assert_eq!(first.stats.cut_sizes.values().sum::<usize>(), 50);
+        assert_eq!(first.stats.smallest(), Some(3));
+        assert_eq!(first.graph.weight(), 54);
+        let other = graph
+            .kager_min_cut(&Options {
+                seed: 48,
+                ..options
+            })
+            .unwrap();
+        assert_ne!(first.stats, other.stats);
+    }
+
+    #[test]
+    fn test_target() {
+        let graph = Graph::new(EXAMPLE);
+        let run = graph.kager_min_cut(&Options::default()).unwrap();
+        assert_eq!(run.stats.cut_sizes.get(&3), Some(&1));
+        assert_eq!(
+            run.stats.trials,
+            run.stats.cut_sizes.values().sum::<usize>()
+        );
+        // stopping at the first cut says nothing of how often the trials find it
+        assert!(run.stats.stopped);
+        assert_eq!(run.stats.success_probability(), None);
+        assert!(run.stats.to_string().ends_with(
+            "success probability: unknown, the trials stopped at the first cut, see --exhaustive"
+        ));
+
+        let options = Options {
+            target: Some(2),
+            trials: 20,
+            ..Options::default()
+        };
+        let stats = graph.kager_min_cut(&options).unwrap_err();
+        assert_eq!(stats.trials, 20);
+        assert_eq!(stats.smallest(), Some(3));
+        assert!(stats.success_probability().is_some());
+    }
+
+    #[test]
+    fn test_karger_stein() {
+        let graph = Graph::new(EXAMPLE);
+        let options = Options {
+            algorithm: Algorithm::KargerStein,
+            target: None,
+            trials: 20,
+            ..Options::default()
+        };
+        let run = graph.kager_min_cut(&options).unwrap();
+        assert_eq!(run.graph.weight(), 54);
+        assert_eq!(run.graph.component_sizes(), vec![9, 6]);
+        assert_eq!(run.stats.smallest(), Some(3));
+
+        let karger = graph
+            .kager_min_cut(&Options {
+                algorithm: Algorithm::Karger,
+                ..options
+            })
+            .unwrap();
+        // each recursive run tries many contractions, it should find the cut more often
+        assert!(
+            run.stats.success_probability().unwrap() >= karger.stats.success_probability().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats {
+            trials: 4,
+            cut_sizes: BTreeMap::from([(3, 1), (5, 3)]),
+            stopped: false,
+        };
+        assert_eq!(stats.success_probability(), Some(0.25));
+        assert!((stats.confidence(2).unwrap() - 0.4375).abs() < 1e-9);
+        assert_eq!(
+            stats.to_string(),
+            "trials: 4\ncuts of 3 wires: 1\ncuts of 5 wires: 3\nsuccess probability: 25.00%"
+        );
+        assert_eq!(Stats::default().success_probability(), Some(0.0));
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(Options::parse(&[]), Ok(Options::default()));
+        assert_eq!(
+            Options::parse(&args("--stein --seed 7 --trials 30 --wires 4")),
+            Ok(Options {
+                algorithm: Algorithm::KargerStein,
+                seed: 7,
+                trials: 30,
+                target: Some(4),
+            })
+        );
+        assert_eq!(Options::parse(&args("--exhaustive")).unwrap().target, None);
+        assert_eq!(
+            Options::parse(&args("--seed")),
+            Err("`--seed` needs a value".to_string())
+        );
+        assert_eq!(
+            Options::parse(&args("--trials x")),
+            Err("`x` is not a number".to_string())
+        );
+        assert_eq!(
+            Options::parse(&args("--fast")),
+            Err("unknown option `--fast`".to_string())
+        );
+    }
+}
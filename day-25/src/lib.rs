@@ -1 +1,11 @@
+pub mod adjacency;
+pub mod cuts;
+pub mod formats;
+pub mod karger;
+pub mod min_cut;
+mod union_find;
+
+#[cfg(test)]
+mod fixtures;
+
 pub mod part_1;
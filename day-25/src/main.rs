@@ -1,4 +1,4 @@
-mod part_1;
+use day_25_lib::part_1;
 
 fn main() {
     let input = include_str!("./inputs/input.txt");
@@ -0,0 +1,335 @@
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use crate::adjacency::Adjacency;
+
+/// Wires splitting the diagram in two groups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    // by label, the lower label first, sorted
+    pub edges: Vec<(String, String)>,
+    // sorted, the first group has the lowest label
+    pub sides: [Vec<String>; 2],
+}
+
+impl Cut {
+    // The cut between the vertices in `side` and the others
+    pub(crate) fn from_side(graph: &Adjacency, side: &[bool]) -> Self {
+        let mut edges: Vec<(String, String)> = graph
+            .edges()
+            .iter()
+            .filter(|(a, b)| side[*a] != side[*b])
+            .map(|(a, b)| {
+                let (a, b) = (graph.label(*a), graph.label(*b));
+                (a.min(b).to_string(), a.max(b).to_string())
+            })
+            .collect();
+        edges.sort();
+
+        let mut sides = [Vec::new(), Vec::new()];
+        for (id, inside) in side.iter().enumerate() {
+            sides[*inside as usize].push(graph.label(id).to_string());
+        }
+        for side in sides.iter_mut() {
+            side.sort();
+        }
+        if sides[0].is_empty() || (!sides[1].is_empty() && sides[1][0] < sides[0][0]) {
+            sides.swap(0, 1);
+        }
+        Cut { edges, sides }
+    }
+
+    pub fn sizes(&self) -> [usize; 2] {
+        [self.sides[0].len(), self.sides[1].len()]
+    }
+
+    /// The product of the groups' sizes, the puzzle's answer
+    pub fn weight(&self) -> usize {
+        self.sides[0].len() * self.sides[1].len()
+    }
+}
+
+/// Minimum cut by Stoer–Wagner: every phase orders the vertices by how connected
+/// they are to the ones before, the cut around the last one is a candidate,
+/// then the last two are merged. `None` with less than two vertices.
+pub fn stoer_wagner(graph: &Adjacency) -> Option<Cut> {
+    let n = graph.len();
+    if n < 2 {
+        return None;
+    }
+    // wires between the merged vertices
+    let mut weights: Vec<HashMap<usize, usize>> = (0..n)
+        .map(|id| graph.neighbours(id).iter().map(|&to| (to, 1)).collect())
+        .collect();
+    // the original vertices in every merged one
+    let mut members: Vec<Vec<usize>> = (0..n).map(|id| vec![id]).collect();
+    let mut active: Vec<usize> = (0..n).collect();
+    let mut best: Option<(usize, Vec<usize>)> = None;
+
+    while active.len() > 1 {
+        let mut added = vec![false; n];
+        let mut keys = vec![0; n];
+        let mut heap = BinaryHeap::from([(0, active[0])]);
+        let mut order = Vec::with_capacity(active.len());
+        while let Some((key, id)) = heap.pop() {
+            if added[id] || key != keys[id] {
+                continue;
+            }
+            added[id] = true;
+            order.push((id, key));
+            for (&to, &weight) in &weights[id] {
+                if !added[to] {
+                    keys[to] += weight;
+                    heap.push((keys[to], to));
+                }
+            }
+        }
+        if order.len() < active.len() {
+            // the vertices reached are disconnected from the others
+            let side: Vec<usize> = order
+                .iter()
+                .flat_map(|(id, _)| &members[*id])
+                .copied()
+                .collect();
+            return Some(cut_around(graph, &side));
+        }
+
+        let (s, _) = order[order.len() - 2];
+        let (t, weight) = order[order.len() - 1];
+        if best.as_ref().is_none_or(|(best, _)| weight < *best) {
+            best = Some((weight, members[t].clone()));
+        }
+
+        for (to, weight) in std::mem::take(&mut weights[t]) {
+            let back = weights[to].remove(&t).unwrap();
+            if to != s {
+                *weights[s].entry(to).or_insert(0) += weight;
+                *weights[to].entry(s).or_insert(0) += back;
+            }
+        }
+        let merged = std::mem::take(&mut members[t]);
+        members[s].extend(merged);
+        active.retain(|id| *id != t);
+    }
+    best.map(|(_, side)| cut_around(graph, &side))
+}
+
+fn cut_around(graph: &Adjacency, ids: &[usize]) -> Cut {
+    let mut side = vec![false; graph.len()];
+    for id in ids {
+        side[*id] = true;
+    }
+    Cut::from_side(graph, &side)
+}
+
+/// Minimum cut by Edmonds–Karp: the smallest maximum flow from the first vertex
+/// to any other one, every wire carrying one unit each way. `None` with less than two vertices.
+pub fn edmonds_karp(graph: &Adjacency) -> Option<Cut> {
+    if graph.len() < 2 {
+        return None;
+    }
+    let mut network = Network::new(graph);
+    let mut best: Option<(usize, Vec<bool>)> = None;
+    for sink in 1..graph.len() {
+        let limit = best.as_ref().map_or(usize::MAX, |(flow, _)| *flow);
+        let flow = network.max_flow(0, sink, limit);
+        if flow < limit {
+            best = Some((flow, network.source_side(0)));
+        }
+    }
+    best.map(|(_, side)| Cut::from_side(graph, &side))
+}
+
+/// Residual network of the diagram, the wire `i` is the arcs `2i` and `2i + 1`
+pub(crate) struct Network {
+    arcs: Vec<(usize, usize)>,
+    // by vertex, the arcs leaving it
+    leaving: Vec<Vec<usize>>,
+    // -1, 0 or 1 unit by arc, the arcs of a wire carry opposite flows
+    flow: Vec<i8>,
+}
+
+impl Network {
+    pub(crate) fn new(graph: &Adjacency) -> Self {
+        let mut arcs = Vec::with_capacity(graph.edges().len() * 2);
+        let mut leaving = vec![Vec::new(); graph.len()];
+        for (a, b) in graph.edges() {
+            leaving[*a].push(arcs.len());
+            arcs.push((*a, *b));
+            leaving[*b].push(arcs.len());
+            arcs.push((*b, *a));
+        }
+        let flow = vec![0; arcs.len()];
+        Network {
+            arcs,
+            leaving,
+            flow,
+        }
+    }
+
+    /// Flow from `source` to `sink` by shortest augmenting paths, it stops once it reaches `limit`
+    pub(crate) fn max_flow(&mut self, source: usize, sink: usize, limit: usize) -> usize {
+        self.flow.fill(0);
+        let mut total = 0;
+        while total < limit {
+            let Some(path) = self.augmenting_path(source, sink) else {
+                break;
+            };
+            for arc in path {
+                self.flow[arc] += 1;
+                self.flow[arc ^ 1] -= 1;
+            }
+            total += 1;
+        }
+        total
+    }
+
+    // The arcs of a shortest path with room for one more unit
+    fn augmenting_path(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut through: Vec<Option<usize>> = vec![None; self.leaving.len()];
+        let mut seen = vec![false; self.leaving.len()];
+        seen[source] = true;
+        let mut queue = VecDeque::from([source]);
+        while let Some(id) = queue.pop_front() {
+            if id == sink {
+                let mut path = Vec::new();
+                let mut at = sink;
+                while let Some(arc) = through[at] {
+                    path.push(arc);
+                    at = self.arcs[arc].0;
+                }
+                return Some(path);
+            }
+            for &arc in &self.leaving[id] {
+                let to = self.arcs[arc].1;
+                if !seen[to] && self.flow[arc] < 1 {
+                    seen[to] = true;
+                    through[to] = Some(arc);
+                    queue.push_back(to);
+                }
+            }
+        }
+        None
+    }
+
+    // The vertices `id` can still send a unit to
+    pub(crate) fn forward(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.leaving[id]
+            .iter()
+            .filter(|arc| self.flow[**arc] < 1)
+            .map(|arc| self.arcs[*arc].1)
+    }
+
+    // The vertices that can still send a unit to `id`
+    pub(crate) fn backward(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.leaving[id]
+            .iter()
+            .filter(|arc| self.flow[**arc ^ 1] < 1)
+            .map(|arc| self.arcs[*arc].1)
+    }
+
+    /// The vertices still reachable from `source` after the last flow
+    pub(crate) fn source_side(&self, source: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.leaving.len()];
+        seen[source] = true;
+        let mut stack = vec![source];
+        while let Some(id) = stack.pop() {
+            for &arc in &self.leaving[id] {
+                let to = self.arcs[arc].1;
+                if !seen[to] && self.flow[arc] < 1 {
+                    seen[to] = true;
+                    stack.push(to);
+                }
+            }
+        }
+        seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        fixtures::EXAMPLE,
+        karger::{Graph, Options},
+    };
+
+    fn pairs(edges: &[(&str, &str)]) -> Vec<(String, String)> {
+        edges
+            .iter()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let graph = Adjacency::new(EXAMPLE);
+        let expected = pairs(&[("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+        for cut in [stoer_wagner(&graph), edmonds_karp(&graph)] {
+            let cut = cut.unwrap();
+            assert_eq!(cut.edges, expected);
+            assert_eq!(cut.sides[0], vec!["bvb", "hfx", "jqt", "ntq", "rhn", "xhk"]);
+            assert_eq!(cut.weight(), 54);
+        }
+    }
+
+    #[test]
+    fn test_agrees_with_karger() {
+        let graph = Adjacency::new(EXAMPLE);
+        let karger = Graph::new(EXAMPLE)
+            .kager_min_cut(&Options::default())
+            .unwrap()
+            .graph;
+        let cut = karger.cut();
+        assert_eq!(cut.edges.len(), karger.crossing_edges().len());
+        assert_eq!(Some(&cut), stoer_wagner(&graph).as_ref());
+        assert_eq!(Some(&cut), edmonds_karp(&graph).as_ref());
+    }
+
+    // The smallest cut over every split, for small graphs
+    fn brute_force(graph: &Adjacency) -> usize {
+        let n = graph.len();
+        (1..1usize << (n - 1))
+            .map(|mask| {
+                let side: Vec<bool> = (0..n).map(|id| mask & (1 << id) != 0).collect();
+                Cut::from_side(graph, &side).edges.len()
+            })
+            .min()
+            .unwrap()
+    }
+
+    fn random_graph(random: &mut StdRng) -> Adjacency {
+        let n = random.gen_range(2..10);
+        let mut lines = Vec::new();
+        for id in 0..n {
+            let neighbours: Vec<String> = (0..n)
+                .filter(|other| *other != id && random.gen_bool(0.3))
+                .map(|other| format!("v{}", other))
+                .collect();
+            lines.push(format!("v{}: {}", id, neighbours.join(" ")));
+        }
+        Adjacency::new(&lines.join("\n"))
+    }
+
+    #[test]
+    fn test_random_graphs() {
+        let mut random = StdRng::seed_from_u64(25);
+        for _ in 0..300 {
+            let graph = random_graph(&mut random);
+            let expected = brute_force(&graph);
+            let stoer_wagner = stoer_wagner(&graph).unwrap();
+            let edmonds_karp = edmonds_karp(&graph).unwrap();
+            assert_eq!(stoer_wagner.edges.len(), expected);
+            assert_eq!(edmonds_karp.edges.len(), expected);
+            assert!(stoer_wagner.sides.iter().all(|side| !side.is_empty()));
+            assert!(edmonds_karp.sides.iter().all(|side| !side.is_empty()));
+        }
+    }
+
+    #[test]
+    fn test_too_small() {
+        assert_eq!(stoer_wagner(&Adjacency::new("")), None);
+        assert_eq!(edmonds_karp(&Adjacency::new("")), None);
+    }
+}
@@ -0,0 +1,74 @@
+/// Disjoint sets of ids, the sets are merged by size so every root is close
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    // by root, how many ids are in its set
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            count: len,
+        }
+    }
+
+    pub fn find(&self, mut id: usize) -> usize {
+        while self.parent[id] != id {
+            id = self.parent[id];
+        }
+        id
+    }
+
+    /// Merges the sets of `a` and `b`, false if they're the same set already
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.count -= 1;
+        true
+    }
+
+    /// How many ids are in the set of `id`
+    pub fn size(&self, id: usize) -> usize {
+        self.size[self.find(id)]
+    }
+
+    /// How many sets are left
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The root of every set
+    pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.parent.len()).filter(|id| self.parent[*id] == *id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union() {
+        let mut sets = UnionFind::new(5);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(3, 4));
+        assert!(sets.union(1, 4));
+        assert!(!sets.union(0, 3));
+        assert_eq!(sets.count(), 2);
+        assert_eq!(sets.size(3), 4);
+        assert_eq!(sets.size(2), 1);
+        assert_eq!(sets.find(0), sets.find(4));
+        assert_eq!(sets.roots().count(), 2);
+    }
+}
@@ -64,6 +64,7 @@ pub fn process(input: &str) -> u32 {
 
     But is much faster in every other case.
 */
+#[cfg(not(target_arch = "wasm32"))]
 fn search_lowest(
     number_of_threads: u32,
     seed_ranges: Arc<Vec<MapRange>>,
@@ -126,6 +127,23 @@ fn search_lowest(
     return lowest_location;
 }
 
+// wasm has no threads, the worker searches the locations in order and stops at the first seed
+#[cfg(target_arch = "wasm32")]
+fn search_lowest(
+    _number_of_threads: u32,
+    seed_ranges: Arc<Vec<MapRange>>,
+    maps: Arc<Maps>,
+    start: u32,
+    end: u32,
+) -> Option<u32> {
+    (start..end).find(|location| {
+        let seed = maps.location_to_seed(*location);
+        seed_ranges
+            .iter()
+            .any(|seed_range| seed >= seed_range.start && seed < seed_range.end)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
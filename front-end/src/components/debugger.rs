@@ -0,0 +1,218 @@
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+
+use crate::{
+    components::results::Solution,
+    worker::debugger::{Execution, RangeStep, Step, Trace, Workflow},
+};
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub solutions: Vec<Solution>,
+}
+
+#[function_component(Debugger)]
+pub fn debugger(props: &Props) -> Html {
+    props
+        .solutions
+        .iter()
+        .filter_map(|solution| {
+            let trace = solution.output.as_ref()?.trace.clone()?;
+            Some(html! {
+              <div class="debugger" key={format!("{}-{}", solution.day, solution.part)}>
+                <h3>{format!("Day {} part {} debugger", solution.day.trim_start_matches("day"), solution.part.trim_start_matches("part"))}</h3>
+                <Stepper trace={trace} />
+              </div>
+            })
+        })
+        .collect::<Html>()
+}
+
+#[derive(Clone, PartialEq, Properties)]
+struct StepperProps {
+    trace: Trace,
+}
+
+#[function_component(Stepper)]
+fn stepper(props: &StepperProps) -> Html {
+    let piece = use_state(|| 0_usize);
+    let step = use_state(|| 0_usize);
+
+    // a new input can have fewer parts than the one picked before
+    let selected = match &props.trace.execution {
+        Execution::Pieces(pieces) => (*piece).min(pieces.len().saturating_sub(1)),
+        Execution::Ranges(_) => 0,
+    };
+    let total = match &props.trace.execution {
+        Execution::Pieces(pieces) => pieces.get(selected).map_or(0, |trace| trace.steps.len()),
+        Execution::Ranges(steps) => steps.len(),
+    };
+    if total == 0 {
+        return html! {<p>{"Nothing to step through"}</p>};
+    }
+    let current = (*step).min(total - 1);
+
+    let go_to = |target: fn(usize, usize) -> usize| {
+        let step = step.clone();
+        Callback::from(move |_: MouseEvent| step.set(target(current, total)))
+    };
+    let controls = html! {
+      <div class="debugger-controls">
+        <button onclick={go_to(|_, _| 0)}>{"First"}</button>
+        <button onclick={go_to(|current, _| current.saturating_sub(1))}>{"Previous"}</button>
+        <span>{format!("Step {} of {}", current + 1, total)}</span>
+        <button onclick={go_to(|current, total| (current + 1).min(total - 1))}>{"Next"}</button>
+        <button onclick={go_to(|_, total| total - 1)}>{"Last"}</button>
+      </div>
+    };
+
+    let workflows = &props.trace.workflows;
+    let body = match &props.trace.execution {
+        Execution::Pieces(pieces) => {
+            let on_piece = {
+                let piece = piece.clone();
+                let step = step.clone();
+                Callback::from(move |event: Event| {
+                    let value = event
+                        .target()
+                        .unwrap()
+                        .dyn_into::<web_sys::HtmlSelectElement>()
+                        .unwrap()
+                        .value();
+                    piece.set(value.parse().unwrap_or(0));
+                    step.set(0);
+                })
+            };
+            let options = pieces
+                .iter()
+                .enumerate()
+                .map(|(i, trace)| {
+                    let result = if trace.accepted { "A" } else { "R" };
+                    html! {
+                      <option value={i.to_string()} selected={i == selected}>{format!("{} -> {}", trace.piece, result)}</option>
+                    }
+                })
+                .collect::<Html>();
+            let steps = &pieces[selected].steps;
+            html! {
+              <>
+                <select id="debugger-piece" onchange={on_piece}>{options}</select>
+                {controls}
+                {create_path(steps, current)}
+                {create_step(workflows, &steps[current])}
+              </>
+            }
+        }
+        Execution::Ranges(steps) => {
+            let step = &steps[current];
+            html! {
+              <>
+                {controls}
+                {create_workflow(workflows, &step.workflow, step.rule, !step.matched.is_empty())}
+                {create_ranges(&props.trace.variables, step)}
+              </>
+            }
+        }
+    };
+
+    html! {
+      <div class="stepper">{body}</div>
+    }
+}
+
+// Workflows the part went through up to the current step
+fn create_path(steps: &[Step], current: usize) -> Html {
+    let mut path: Vec<&str> = Vec::new();
+    for step in &steps[..=current] {
+        if path.last() != Some(&step.workflow.as_str()) {
+            path.push(&step.workflow);
+        }
+    }
+    html! {
+      <p class="debugger-path">{path.join(" -> ")}</p>
+    }
+}
+
+fn create_step(workflows: &[Workflow], step: &Step) -> Html {
+    let branch = if step.matched {
+        format!("matched, goes to {}", step.target)
+    } else {
+        "not matched, tests the next rule".to_string()
+    };
+    html! {
+      <>
+        {create_workflow(workflows, &step.workflow, step.rule, step.matched)}
+        <p class="debugger-branch">{branch}</p>
+      </>
+    }
+}
+
+fn create_workflow(workflows: &[Workflow], name: &str, rule: usize, matched: bool) -> Html {
+    let rules = workflows
+        .iter()
+        .find(|workflow| workflow.name == name)
+        .map(|workflow| workflow.rules.as_slice())
+        .unwrap_or_default();
+    let rules = rules
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let class = match (i == rule, matched) {
+                (true, true) => classes!("rule", "current", "taken"),
+                (true, false) => classes!("rule", "current", "skipped"),
+                (false, _) => classes!("rule"),
+            };
+            let separator = if i + 1 < rules.len() { "," } else { "" };
+            html! {
+              <><span class={class}>{text}</span>{separator}</>
+            }
+        })
+        .collect::<Html>();
+    html! {
+      <pre class="workflow">{name}{"{"}{rules}{"}"}</pre>
+    }
+}
+
+fn create_ranges(variables: &[String], step: &RangeStep) -> Html {
+    // a `!=` rule splits a range in two, both halves go in the same cell
+    let format = |batches: &[Vec<(u64, u64)>], i: usize| {
+        let mut ranges: Vec<String> = batches
+            .iter()
+            .map(|ranges| format!("{}..={}", ranges[i].0, ranges[i].1))
+            .collect();
+        ranges.dedup();
+        match ranges.is_empty() {
+            true => "-".to_string(),
+            false => ranges.join(", "),
+        }
+    };
+    let rows = variables
+        .iter()
+        .enumerate()
+        .map(|(i, variable)| {
+            html! {
+              <tr>
+                <td>{variable}</td>
+                <td>{format(std::slice::from_ref(&step.ranges), i)}</td>
+                <td>{format(&step.matched, i)}</td>
+                <td>{format(&step.unmatched, i)}</td>
+              </tr>
+            }
+        })
+        .collect::<Html>();
+    html! {
+      <table class="ranges">
+        <thead>
+          <tr>
+            <th>{"Variable"}</th>
+            <th>{"Ranges"}</th>
+            <th>{format!("Sent to {}", step.target)}</th>
+            <th>{"Next rule"}</th>
+          </tr>
+        </thead>
+        <tbody>
+          {rows}
+        </tbody>
+      </table>
+    }
+}
@@ -1,60 +1,386 @@
 use crate::{
     components::{select::Select, text_area::TextArea},
+    router::{self, Route, DAY_RANGE, PART_RANGE},
+    storage,
     worker::Input,
 };
 
 use yew::prelude::*;
+use yew_router::prelude::*;
 
 #[derive(Clone, PartialEq, Properties)]
 pub struct Props {
-    pub callback: Callback<Input>,
+    pub day: usize,
+    pub part: usize,
+    pub callback: Callback<Vec<Input>>,
 }
 
 #[function_component(Form)]
 pub fn text_area(props: &Props) -> Html {
-    let callback = props.callback.clone();
-    let part_range = 1..=2;
-    let day_range = 1..=25;
+    let navigator = use_navigator().unwrap();
 
-    let input = use_state(|| "".to_string());
-    let part = use_state(|| "part2".to_string());
-    let day = use_state(|| "day25".to_string());
+    let day = format!("day{}", props.day);
 
-    let callback_input = input.clone();
-    let callback_part = part.clone();
-    let callback_day = day.clone();
+    // read once, a shared input replaces the stored one for its day
+    let shared = use_memo((), |_| router::shared_input());
+    let input = use_state(|| match shared.as_ref() {
+        Some(shared) => shared.clone(),
+        None => storage::load_input(&day).unwrap_or_default(),
+    });
+    let share_link = use_state(|| None::<String>);
 
+    // the day comes from the route, so the input is swapped when the route changes
+    let loaded_day = use_mut_ref(|| day.clone());
+    {
+        let input = input.clone();
+        let share_link = share_link.clone();
+        use_effect_with(day.clone(), move |day| {
+            if *loaded_day.borrow() != *day {
+                input.set(storage::load_input(day).unwrap_or_default());
+                share_link.set(None);
+                *loaded_day.borrow_mut() = day.clone();
+            }
+        });
+    }
+
+    {
+        // opening a share link stores its input and solves it right away
+        let callback = props.callback.clone();
+        let inputs = inputs_for(props.day, [props.part], &input);
+        let day = day.clone();
+        use_effect_with((), move |_| {
+            if let Some(shared) = shared.as_ref() {
+                storage::save_input(&day, shared);
+                callback.emit(inputs);
+            }
+        });
+    }
+
+    let callback_input = input.clone();
     let callback_input = Callback::from(move |value: String| {
         callback_input.set(value);
     });
 
-    let callback_part = Callback::from(move |value: String| {
-        callback_part.set(value);
-    });
+    let callback_part = {
+        let navigator = navigator.clone();
+        let day = props.day;
+        Callback::from(move |value: String| {
+            let part = selected("part", &value);
+            navigator.push(&Route::Solve { day, part });
+        })
+    };
 
-    let callback_day = Callback::from(move |value: String| {
-        callback_day.set(value);
-    });
+    let callback_day = {
+        let navigator = navigator.clone();
+        let part = props.part;
+        Callback::from(move |value: String| {
+            let day = selected("day", &value);
+            navigator.push(&Route::Solve { day, part });
+        })
+    };
 
-    let on_submit = Callback::from(move |event: SubmitEvent| {
-        event.prevent_default();
-        let input = input.clone().to_string();
-        let part = part.clone().to_string();
-        let day = day.clone().to_string();
+    let on_submit = {
+        let callback = props.callback.clone();
+        let input = input.clone();
+        let day = day.clone();
+        let (day_number, part) = (props.day, props.part);
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+            storage::save_input(&day, &input);
+            callback.emit(inputs_for(day_number, [part], &input));
+        })
+    };
 
-        let input = Input { input, part, day };
+    let on_both = {
+        let callback = props.callback.clone();
+        let input = input.clone();
+        let day = day.clone();
+        let day_number = props.day;
+        Callback::from(move |_: MouseEvent| {
+            storage::save_input(&day, &input);
+            callback.emit(inputs_for(day_number, PART_RANGE, &input));
+        })
+    };
 
-        callback.emit(input);
-    });
+    let on_all = {
+        let callback = props.callback.clone();
+        let input = input.clone();
+        let day = day.clone();
+        Callback::from(move |_: MouseEvent| {
+            storage::save_input(&day, &input);
+            callback.emit(stored_inputs());
+        })
+    };
+
+    let on_share = {
+        let input = input.clone();
+        let share_link = share_link.clone();
+        let (day, part) = (props.day, props.part);
+        Callback::from(move |_: MouseEvent| {
+            navigator.replace(&Route::Solve { day, part });
+            let _ = gloo::utils::window()
+                .location()
+                .set_hash(&router::share_fragment(&input));
+            share_link.set(Some(router::share_link(day, part, &input)));
+        })
+    };
+
+    let share_section = match share_link.as_ref() {
+        Some(link) => html! {
+          <input id="share-link" readonly=true value={link.clone()} />
+        },
+        None => html! {},
+    };
 
     html! {
       <div class="input-section" onsubmit={on_submit}>
         <form>
-          <Select id="day" label="Day" range={day_range} callback={callback_day} />
-          <Select id="part" label="Part" range={part_range} callback={callback_part} />
-          <TextArea id="input" placeholder="Enter your input here" callback={callback_input} />
+          <Select id="day" label="Day" range={DAY_RANGE} value={props.day} callback={callback_day} />
+          <Select id="part" label="Part" range={PART_RANGE} value={props.part} callback={callback_part} />
+          <TextArea id="input" placeholder="Enter your input here" value={input.to_string()} callback={callback_input} />
           <button id="submit">{"Submit"}</button>
+          <button id="submit-both" type="button" onclick={on_both}>{"Solve both parts"}</button>
+          <button id="submit-all" type="button" onclick={on_all}>{"Run all days"}</button>
+          <button id="share" type="button" onclick={on_share}>{"Share"}</button>
+          {share_section}
         </form >
       </div>
     }
 }
+
+// Every (day, part) pair that has an input saved in the browser storage
+fn stored_inputs() -> Vec<Input> {
+    let mut inputs = Vec::new();
+    for day in DAY_RANGE {
+        let input = match storage::load_input(&format!("day{}", day)) {
+            Some(input) => input,
+            None => continue,
+        };
+        inputs.extend(inputs_for(day, PART_RANGE, &input));
+    }
+    inputs
+}
+
+// Number picked by a select, its values look like `day12` or `part2`
+fn selected(id: &str, value: &str) -> usize {
+    value.trim_start_matches(id).parse().unwrap_or(1)
+}
+
+// Worker inputs for the given parts of a day
+fn inputs_for(day: usize, parts: impl IntoIterator<Item = usize>, input: &str) -> Vec<Input> {
+    parts
+        .into_iter()
+        .map(|part| Input {
+            input: input.to_string(),
+            part: format!("part{}", part),
+            day: format!("day{}", day),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::select::option_value;
+    use crate::worker;
+
+    #[test]
+    fn test_selected_round_trips_option_values() {
+        for day in DAY_RANGE {
+            assert_eq!(selected("day", &option_value("day", day)), day);
+        }
+        for part in PART_RANGE {
+            assert_eq!(selected("part", &option_value("part", part)), part);
+        }
+        assert_eq!(selected("day", "garbage"), 1);
+    }
+
+    #[test]
+    fn test_inputs_for() {
+        let inputs = inputs_for(12, PART_RANGE, "???.### 1,1,3");
+        let pairs: Vec<(&str, &str)> = inputs
+            .iter()
+            .map(|input| (input.day.as_str(), input.part.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("day12", "part1"), ("day12", "part2")]);
+        assert!(inputs.iter().all(|input| input.input == "???.### 1,1,3"));
+    }
+
+    #[test]
+    fn test_inputs_are_understood_by_the_worker() {
+        let input = inputs_for(6, [2], "Time:      7  15   30\nDistance:  9  40  200");
+        let result = worker::solve(input[0].clone());
+        assert_eq!(result, "71503");
+    }
+}
+
+// Mounts the form in the browser and drives it like a user, checking what it hands to the worker
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_test {
+    use std::{cell::RefCell, rc::Rc, time::Duration};
+
+    use gloo::storage::{LocalStorage, Storage};
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+    use web_sys::{Element, Event, EventInit, HtmlElement, HtmlSelectElement, HtmlTextAreaElement};
+    use yew_router::history::{AnyHistory, MemoryHistory};
+
+    use super::*;
+    use crate::components::select::option_value;
+
+    // the form needs a document and `localStorage`, which Node doesn't have
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Clone, PartialEq, Properties)]
+    struct HarnessProps {
+        path: AttrValue,
+        callback: Callback<Vec<Input>>,
+    }
+
+    // The form under a router starting at `path`, fed by the route like the app does
+    #[function_component(Harness)]
+    fn harness(props: &HarnessProps) -> Html {
+        let path = props.path.to_string();
+        let history = use_memo((), move |_| {
+            AnyHistory::from(MemoryHistory::with_entries([path]))
+        });
+        html! {
+            <Router history={(*history).clone()}>
+                <Routed callback={props.callback.clone()} />
+            </Router>
+        }
+    }
+
+    #[derive(Clone, PartialEq, Properties)]
+    struct RoutedProps {
+        callback: Callback<Vec<Input>>,
+    }
+
+    #[function_component(Routed)]
+    fn routed(props: &RoutedProps) -> Html {
+        let route = use_route::<Route>().unwrap_or(Route::Home);
+        let (day, part) = route.selection();
+        html! {
+            <Form day={day} part={part} callback={props.callback.clone()} />
+        }
+    }
+
+    // Lets yew render the pending updates
+    async fn settle() {
+        yew::platform::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // The root the form is mounted in and every batch of inputs it emits
+    async fn mount(path: &str) -> (Element, Rc<RefCell<Vec<Vec<Input>>>>) {
+        // a stored input would take the place of the one typed by the test
+        LocalStorage::clear();
+        let document = gloo::utils::document();
+        let root = document.create_element("div").unwrap();
+        document.body().unwrap().append_child(&root).unwrap();
+
+        let emitted = Rc::new(RefCell::new(Vec::new()));
+        let callback = {
+            let emitted = emitted.clone();
+            Callback::from(move |inputs: Vec<Input>| emitted.borrow_mut().push(inputs))
+        };
+        yew::Renderer::<Harness>::with_root_and_props(
+            root.clone(),
+            HarnessProps {
+                path: path.to_string().into(),
+                callback,
+            },
+        )
+        .render();
+        settle().await;
+        (root, emitted)
+    }
+
+    // Every test mounts its own form, so the elements are looked up inside its root
+    fn find<T: JsCast>(root: &Element, selector: &str) -> T {
+        root.query_selector(selector)
+            .unwrap()
+            .unwrap_or_else(|| panic!("no `{}` in the form", selector))
+            .dyn_into()
+            .unwrap()
+    }
+
+    // yew listens at the root, so the event has to bubble up to it
+    fn change(element: &Element) {
+        let mut init = EventInit::new();
+        init.bubbles(true);
+        let event = Event::new_with_event_init_dict("change", &init).unwrap();
+        element.dispatch_event(&event).unwrap();
+    }
+
+    async fn type_input(root: &Element, input: &str) {
+        let text_area: HtmlTextAreaElement = find(root, "#input");
+        text_area.set_value(input);
+        change(&text_area);
+        settle().await;
+    }
+
+    async fn pick(root: &Element, id: &str, value: usize) {
+        let select: HtmlSelectElement = find(root, &format!("#{}", id));
+        select.set_value(&option_value(id, value));
+        change(&select);
+        settle().await;
+    }
+
+    async fn click(root: &Element, id: &str) {
+        let button: HtmlElement = find(root, &format!("#{}", id));
+        button.click();
+        settle().await;
+    }
+
+    fn pairs(inputs: &[Input]) -> Vec<(&str, &str)> {
+        inputs
+            .iter()
+            .map(|input| (input.day.as_str(), input.part.as_str()))
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_submit_emits_the_route_selection() {
+        let (root, emitted) = mount("/day/12/part/2").await;
+        type_input(&root, "???.### 1,1,3").await;
+        click(&root, "submit").await;
+
+        let emitted = emitted.borrow();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(pairs(&emitted[0]), vec![("day12", "part2")]);
+        assert_eq!(emitted[0][0].input, "???.### 1,1,3");
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_selects_change_the_emitted_day_and_part() {
+        let (root, emitted) = mount("/day/1/part/1").await;
+        let day: HtmlSelectElement = find(&root, "#day");
+        assert_eq!(day.value(), "day1");
+
+        pick(&root, "day", 17).await;
+        pick(&root, "part", 2).await;
+        type_input(&root, "2413432311323").await;
+        click(&root, "submit").await;
+        click(&root, "submit-both").await;
+
+        let emitted = emitted.borrow();
+        assert_eq!(pairs(&emitted[0]), vec![("day17", "part2")]);
+        assert_eq!(
+            pairs(&emitted[1]),
+            vec![("day17", "part1"), ("day17", "part2")]
+        );
+        assert!(emitted[1]
+            .iter()
+            .all(|input| input.input == "2413432311323"));
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_emitted_inputs_are_solved_by_the_worker() {
+        let (root, emitted) = mount("/day/6/part/2").await;
+        type_input(&root, "Time:      7  15   30\nDistance:  9  40  200").await;
+        click(&root, "submit").await;
+
+        let input = emitted.borrow()[0][0].clone();
+        assert_eq!(crate::worker::solve(input), "71503");
+    }
+}
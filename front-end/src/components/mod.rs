@@ -1,3 +1,6 @@
+pub mod debugger;
 pub mod form;
+pub mod results;
 pub mod select;
 pub mod text_area;
+pub mod visualization;
@@ -0,0 +1,92 @@
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+
+use crate::{storage, worker::Output};
+
+#[derive(Clone, PartialEq)]
+pub struct Solution {
+    pub day: String,
+    pub part: String,
+    pub output: Option<Output>,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub solutions: Vec<Solution>,
+}
+
+#[function_component(Results)]
+pub fn results(props: &Props) -> Html {
+    // expected answers live in the storage, this only forces a re-render after an edit
+    let edits = use_state(|| 0);
+
+    let rows = props
+        .solutions
+        .iter()
+        .map(|solution| create_row(solution, edits.clone()))
+        .collect::<Html>();
+
+    html! {
+      <table id="results">
+        <thead>
+          <tr>
+            <th>{"Day"}</th>
+            <th>{"Part"}</th>
+            <th>{"Answer"}</th>
+            <th>{"Time"}</th>
+            <th>{"Expected"}</th>
+            <th>{"Status"}</th>
+          </tr>
+        </thead>
+        <tbody>
+          {rows}
+        </tbody>
+      </table>
+    }
+}
+
+fn create_row(solution: &Solution, edits: UseStateHandle<usize>) -> Html {
+    let expected = storage::load_answer(&solution.day, &solution.part);
+
+    let day = solution.day.clone();
+    let part = solution.part.clone();
+    let on_change = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .dyn_into::<web_sys::HtmlInputElement>()
+            .unwrap()
+            .value();
+        storage::save_answer(&day, &part, &value);
+        edits.set(*edits + 1);
+    });
+
+    let (answer, time, status) = match &solution.output {
+        Some(output) => {
+            let status = match &expected {
+                Some(expected) if *expected == output.result => {
+                    html! {<span class="match">{"match"}</span>}
+                }
+                Some(_) => html! {<span class="mismatch">{"mismatch"}</span>},
+                None => html! {},
+            };
+            (
+                output.result.clone(),
+                format!("{:.2} ms", output.elapsed),
+                status,
+            )
+        }
+        None => ("Processing...".to_string(), "".to_string(), html! {}),
+    };
+
+    html! {
+      <tr key={format!("{}-{}", solution.day, solution.part)}>
+        <td>{solution.day.trim_start_matches("day")}</td>
+        <td>{solution.part.trim_start_matches("part")}</td>
+        <td>{answer}</td>
+        <td>{time}</td>
+        <td><input value={expected.unwrap_or_default()} onchange={on_change} /></td>
+        <td>{status}</td>
+      </tr>
+    }
+}
@@ -8,6 +8,7 @@ pub struct Props {
     pub id: AttrValue,
     pub label: AttrValue,
     pub range: RangeInclusive<usize>,
+    pub value: usize,
     pub callback: Callback<String>,
 }
 
@@ -38,7 +39,12 @@ fn create_options(props: &Props) -> Html {
 
     range.map(|value| {
       html! {
-          <option value={format!("{}{}",id.clone(),value)}>{format!("{} {}",label.clone(),value)}</option>
+          <option value={option_value(&id, value)} selected={value == props.value}>{format!("{} {}",label.clone(),value)}</option>
       }
     }).collect::<Html>()
 }
+
+/// Value emitted by the select for an option, e.g. `day12` for the day select
+pub fn option_value(id: &str, value: usize) -> String {
+    format!("{}{}", id, value)
+}
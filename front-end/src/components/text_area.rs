@@ -5,6 +5,8 @@ use yew::prelude::*;
 pub struct Props {
     pub id: AttrValue,
     pub placeholder: AttrValue,
+    #[prop_or_default]
+    pub value: AttrValue,
     pub callback: Callback<String>,
 }
 
@@ -21,6 +23,6 @@ pub fn text_area(props: &Props) -> Html {
         callback.emit(value);
     });
     html! {
-      <textarea id={props.id.clone()} placeholder={props.placeholder.clone()} onchange={on_change}></textarea>
+      <textarea id={props.id.clone()} placeholder={props.placeholder.clone()} value={props.value.clone()} onchange={on_change}></textarea>
     }
 }
@@ -0,0 +1,99 @@
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew::prelude::*;
+
+use crate::{components::results::Solution, worker::render::Render};
+
+// largest side of the canvas in pixels
+const MAX_SIZE: usize = 600;
+const BACKGROUND: &str = "#000000";
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub solutions: Vec<Solution>,
+}
+
+#[function_component(Visualization)]
+pub fn visualization(props: &Props) -> Html {
+    props
+        .solutions
+        .iter()
+        .filter_map(|solution| {
+            let render = solution.output.as_ref()?.render.clone()?;
+            Some(html! {
+              <div class="visualization" key={format!("{}-{}", solution.day, solution.part)}>
+                <h3>{format!("Day {} part {}", solution.day.trim_start_matches("day"), solution.part.trim_start_matches("part"))}</h3>
+                <Canvas render={render.clone()} />
+                {create_legend(&render)}
+              </div>
+            })
+        })
+        .collect::<Html>()
+}
+
+#[derive(Clone, PartialEq, Properties)]
+struct CanvasProps {
+    render: Render,
+}
+
+#[function_component(Canvas)]
+fn canvas(props: &CanvasProps) -> Html {
+    let canvas_ref = use_node_ref();
+
+    let effect_ref = canvas_ref.clone();
+    use_effect_with(props.render.clone(), move |render| {
+        if let Some(canvas) = effect_ref.cast::<HtmlCanvasElement>() {
+            draw(&canvas, render);
+        }
+    });
+
+    html! {
+      <canvas ref={canvas_ref}></canvas>
+    }
+}
+
+fn draw(canvas: &HtmlCanvasElement, render: &Render) {
+    let tile_size = (MAX_SIZE / render.rows.max(render.columns).max(1)).max(1);
+    canvas.set_width((render.columns * tile_size) as u32);
+    canvas.set_height((render.rows * tile_size) as u32);
+
+    let context = match canvas.get_context("2d") {
+        Ok(Some(context)) => context.unchecked_into::<CanvasRenderingContext2d>(),
+        _ => return,
+    };
+
+    let tile_size = tile_size as f64;
+    context.set_fill_style(&JsValue::from_str(BACKGROUND));
+    context.fill_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+
+    for layer in &render.layers {
+        context.set_fill_style(&JsValue::from_str(&layer.color));
+        for (i, j) in &layer.tiles {
+            context.fill_rect(
+                *j as f64 * tile_size,
+                *i as f64 * tile_size,
+                tile_size,
+                tile_size,
+            );
+        }
+    }
+}
+
+fn create_legend(render: &Render) -> Html {
+    let items = render
+        .layers
+        .iter()
+        .map(|layer| {
+            html! {
+              <li>
+                <span class="swatch" style={format!("background-color: {}", layer.color)}></span>
+                {format!("{} ({})", layer.name, layer.tiles.len())}
+              </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+      <ul class="legend">{items}</ul>
+    }
+}
@@ -1,52 +1,96 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use crate::worker::ProcessInput;
+use components::debugger::Debugger;
 use components::form::Form;
+use components::results::{Results, Solution};
+use components::visualization::Visualization;
+use router::Route;
 use worker::Input;
 use yew::platform::spawn_local;
 use yew::prelude::*;
 use yew_agent::oneshot::{use_oneshot_runner, OneshotProvider};
+use yew_router::prelude::*;
 
 mod components;
+mod router;
+mod storage;
 pub mod worker;
 
 #[function_component(App)]
 pub fn app() -> Html {
     html! {
-        <OneshotProvider<ProcessInput> path="/worker.js">
-            <Main />
-        </OneshotProvider<ProcessInput>>
+        <BrowserRouter>
+            <OneshotProvider<ProcessInput> path="/worker.js">
+                <Main />
+            </OneshotProvider<ProcessInput>>
+        </BrowserRouter>
     }
 }
 
 #[function_component(Main)]
 fn main() -> Html {
-    let result = use_state(|| "".to_string());
+    let route = use_route::<Route>().unwrap_or(Route::Home);
+    let (day, part) = route.selection();
+
+    let results = use_state(Vec::<Solution>::new);
+    // a new submission makes the batch that is still running stop updating the table
+    let batch = use_mut_ref(|| 0_usize);
 
     let process_task = use_oneshot_runner::<ProcessInput>();
 
-    let output = result.clone();
+    let output = results.clone();
 
-    let input_callback = Callback::from(move |input: Input| {
+    let input_callback = Callback::from(move |inputs: Vec<Input>| {
         let process_agent = process_task.clone();
         let output = output.clone();
-        output.set("Processing...".to_string());
-        spawn_local(async move {
-            // start the worker
-            let output_value = process_agent.run(input).await;
+        let batch = batch.clone();
+        let current_batch = {
+            let mut batch = batch.borrow_mut();
+            *batch += 1;
+            *batch
+        };
+
+        let mut solutions = inputs
+            .iter()
+            .map(|input| Solution {
+                day: input.day.clone(),
+                part: input.part.clone(),
+                output: None,
+            })
+            .collect::<Vec<_>>();
+        output.set(solutions.clone());
 
-            output.set(output_value);
+        spawn_local(async move {
+            for (i, input) in inputs.into_iter().enumerate() {
+                // start the worker
+                let output_value = process_agent.run(input).await;
+                if is_stale(&batch, current_batch) {
+                    return;
+                }
+                solutions[i].output = Some(output_value);
+                output.set(solutions.clone());
+            }
         });
     });
 
     html! {
             <div class="container">
 
-                        <Form callback={input_callback} />
+                        <Form day={day} part={part} callback={input_callback} />
 
                         <div class="result-section">
-                            <h2>{"Result: "}</h2>
-                            <p id="result">{result.clone().to_string()}</p>
+                            <h2>{"Results: "}</h2>
+                            <Results solutions={(*results).clone()} />
+                            <Visualization solutions={(*results).clone()} />
+                            <Debugger solutions={(*results).clone()} />
                         </div>
 
             </div>
     }
 }
+
+fn is_stale(batch: &Rc<RefCell<usize>>, current_batch: usize) -> bool {
+    *batch.borrow() != current_batch
+}
@@ -0,0 +1,63 @@
+use std::ops::RangeInclusive;
+
+use yew_router::prelude::*;
+
+pub const DAY_RANGE: RangeInclusive<usize> = 1..=25;
+pub const PART_RANGE: RangeInclusive<usize> = 1..=2;
+
+const INPUT_FRAGMENT: &str = "input=";
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+pub enum Route {
+    #[at("/")]
+    Home,
+    #[at("/day/:day/part/:part")]
+    Solve { day: usize, part: usize },
+    #[not_found]
+    #[at("/404")]
+    NotFound,
+}
+
+impl Route {
+    /// Day and part picked by the route, day 1 part 1 when it doesn't pick a valid pair
+    pub fn selection(&self) -> (usize, usize) {
+        match *self {
+            Route::Solve { day, part }
+                if DAY_RANGE.contains(&day) && PART_RANGE.contains(&part) =>
+            {
+                (day, part)
+            }
+            _ => (1, 1),
+        }
+    }
+}
+
+/// Input carried by the URL fragment, when the page was opened from a share link
+pub fn shared_input() -> Option<String> {
+    let hash = gloo::utils::window().location().hash().ok()?;
+    let encoded = hash.trim_start_matches('#').strip_prefix(INPUT_FRAGMENT)?;
+    decode_input(encoded)
+}
+
+/// Link that restores the day, part and input when opened
+pub fn share_link(day: usize, part: usize, input: &str) -> String {
+    let origin = gloo::utils::window()
+        .location()
+        .origin()
+        .unwrap_or_default();
+    let path = Route::Solve { day, part }.to_path();
+    format!("{}{}#{}", origin, path, share_fragment(input))
+}
+
+pub fn share_fragment(input: &str) -> String {
+    format!("{}{}", INPUT_FRAGMENT, encode_input(input))
+}
+
+pub fn encode_input(input: &str) -> String {
+    lz_str::compress_to_encoded_uri_component(input)
+}
+
+pub fn decode_input(encoded: &str) -> Option<String> {
+    let decoded = lz_str::decompress_from_encoded_uri_component(encoded)?;
+    String::from_utf16(&decoded).ok()
+}
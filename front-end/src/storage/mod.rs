@@ -0,0 +1,35 @@
+use gloo::storage::{LocalStorage, Storage};
+
+fn input_key(day: &str) -> String {
+    format!("input/{}", day)
+}
+
+fn answer_key(day: &str, part: &str) -> String {
+    format!("answer/{}/{}", day, part)
+}
+
+pub fn load_input(day: &str) -> Option<String> {
+    LocalStorage::get(input_key(day)).ok()
+}
+
+pub fn save_input(day: &str, input: &str) {
+    if input.is_empty() {
+        LocalStorage::delete(input_key(day));
+        return;
+    }
+    // storage can be full or disabled, the solver still works without it
+    let _ = LocalStorage::set(input_key(day), input);
+}
+
+pub fn load_answer(day: &str, part: &str) -> Option<String> {
+    LocalStorage::get(answer_key(day, part)).ok()
+}
+
+pub fn save_answer(day: &str, part: &str, answer: &str) {
+    let answer = answer.trim();
+    if answer.is_empty() {
+        LocalStorage::delete(answer_key(day, part));
+        return;
+    }
+    let _ = LocalStorage::set(answer_key(day, part), answer);
+}
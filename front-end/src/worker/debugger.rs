@@ -0,0 +1,110 @@
+use day_19_lib::debugger;
+use serde::{Deserialize, Serialize};
+
+use super::Input;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Trace {
+    // names of the parts' values, in the order of the ranges
+    pub variables: Vec<String>,
+    pub workflows: Vec<Workflow>,
+    pub execution: Execution,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Execution {
+    // part 1, the rules every part goes through
+    Pieces(Vec<PieceTrace>),
+    // part 2, how the ranges are split on every rule
+    Ranges(Vec<RangeStep>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Workflow {
+    pub name: String,
+    pub rules: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Step {
+    pub workflow: String,
+    pub rule: usize,
+    pub matched: bool,
+    pub target: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PieceTrace {
+    pub piece: String,
+    pub steps: Vec<Step>,
+    pub accepted: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RangeStep {
+    pub workflow: String,
+    pub rule: usize,
+    // (start, end) of every variable, both ends included
+    pub ranges: Vec<(u64, u64)>,
+    pub matched: Vec<Vec<(u64, u64)>>,
+    pub unmatched: Vec<Vec<(u64, u64)>>,
+    pub target: String,
+}
+
+/// Execution trace of the day 19 interpreter, `None` for the other days
+pub fn trace(input: &Input) -> Option<Trace> {
+    if input.day != "day19" {
+        return None;
+    }
+    let program = debugger::Program::compile(&input.input).ok()?;
+    let workflows = program
+        .workflows()
+        .into_iter()
+        .map(|workflow| Workflow {
+            name: workflow.name,
+            rules: workflow.rules,
+        })
+        .collect();
+    let execution = match input.part.as_str() {
+        "part1" => Execution::Pieces(
+            program
+                .trace_pieces()
+                .into_iter()
+                .map(|trace| PieceTrace {
+                    piece: trace.piece,
+                    steps: trace.steps.into_iter().map(step).collect(),
+                    accepted: trace.accepted,
+                })
+                .collect(),
+        ),
+        _ => Execution::Ranges(
+            program
+                .trace_ranges()
+                .into_iter()
+                .map(|step| RangeStep {
+                    workflow: step.workflow,
+                    rule: step.rule,
+                    ranges: step.ranges,
+                    matched: step.matched,
+                    unmatched: step.unmatched,
+                    target: step.target,
+                })
+                .collect(),
+        ),
+    };
+
+    Some(Trace {
+        variables: program.variables().to_vec(),
+        workflows,
+        execution,
+    })
+}
+
+fn step(step: debugger::Step) -> Step {
+    Step {
+        workflow: step.workflow,
+        rule: step.rule,
+        matched: step.matched,
+        target: step.target,
+    }
+}
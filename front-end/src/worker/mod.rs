@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use yew_agent::prelude::*;
 
+use self::{debugger::Trace, render::Render};
+
+pub mod debugger;
+pub mod render;
+
 macro_rules! day {
     ($lib:ident,$part:expr,$input:expr ) => {
         match $part.as_str() {
@@ -18,8 +23,35 @@ pub struct Input {
     pub day: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Output {
+    pub result: String,
+    // elapsed time in milliseconds
+    pub elapsed: f64,
+    pub render: Option<Render>,
+    pub trace: Option<Trace>,
+}
+
 #[oneshot]
-pub fn ProcessInput(input: Input) -> String {
+pub fn ProcessInput(input: Input) -> Output {
+    let start = js_sys::Date::now();
+    // the grid days are solved once for both the answer and the visualization
+    let (result, render) = match render::solve(&input) {
+        Some((result, render)) => (result, Some(render)),
+        None => (solve(input.clone()), None),
+    };
+    let elapsed = js_sys::Date::now() - start;
+    let trace = debugger::trace(&input);
+
+    Output {
+        result,
+        elapsed,
+        render,
+        trace,
+    }
+}
+
+pub fn solve(input: Input) -> String {
     let part = input.part;
     let day = input.day;
     let input = &input.input;
@@ -54,9 +86,9 @@ pub fn ProcessInput(input: Input) -> String {
     }
 }
 
-fn day_24(part: String, _: &str) -> String {
+fn day_24(part: String, input: &str) -> String {
     match part.as_str() {
-        "part1" => "i64 needed".to_string(),
+        "part1" => day_24_lib::part_1::process(input).to_string(),
         _ => "Not implemented".to_string(),
     }
 }
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+use super::Input;
+
+const WALL: &str = "#555555";
+const PATH: &str = "#ff4d4d";
+const HIGHLIGHT: &str = "#ffd700";
+const FILL: &str = "#4caf50";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Render {
+    pub rows: usize,
+    pub columns: usize,
+    pub layers: Vec<Layer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Layer {
+    pub name: String,
+    pub color: String,
+    // (row, column) of every tile painted by this layer
+    pub tiles: Vec<(usize, usize)>,
+}
+
+impl Layer {
+    fn new(name: &str, color: &str, tiles: Vec<(usize, usize)>) -> Self {
+        Self {
+            name: name.to_string(),
+            color: color.to_string(),
+            tiles,
+        }
+    }
+}
+
+/// Answer and render model for the grid days, both from the same solve,
+/// `None` for the days without a visualization. The days' `render` functions
+/// answer like their part's `process` and give the tiles as (row, column).
+pub fn solve(input: &Input) -> Option<(String, Render)> {
+    let text = input.input.as_str();
+    let (result, layers) = match (input.day.as_str(), input.part.as_str()) {
+        ("day10", "part1") => {
+            let (farthest, main_loop) = day_10_lib::render::main_loop(text);
+            (
+                farthest.to_string(),
+                vec![Layer::new("Main loop", HIGHLIGHT, main_loop)],
+            )
+        }
+        ("day10", "part2") => {
+            let (_, main_loop) = day_10_lib::render::main_loop(text);
+            let (inside_count, inside) = day_10_lib::render::inside_tiles(text);
+            (
+                inside_count.to_string(),
+                vec![
+                    Layer::new("Main loop", HIGHLIGHT, main_loop),
+                    Layer::new("Inside", FILL, inside),
+                ],
+            )
+        }
+        ("day16", part) => {
+            let (count, energized) = match part {
+                "part1" => day_16_lib::render::energized_tiles(text),
+                _ => day_16_lib::render::best_energized_tiles(text),
+            };
+            (
+                count.to_string(),
+                vec![
+                    Layer::new("Energized", HIGHLIGHT, energized),
+                    Layer::new(
+                        "Mirrors and splitters",
+                        WALL,
+                        tiles_of(text, &['|', '-', '/', '\\']),
+                    ),
+                ],
+            )
+        }
+        ("day17", part) => {
+            let (heat_loss, path) = match part {
+                "part1" => day_17_lib::render::crucible_path(text),
+                _ => day_17_lib::render::ultra_crucible_path(text),
+            };
+            (
+                heat_loss.to_string(),
+                vec![Layer::new("Crucible path", PATH, path)],
+            )
+        }
+        ("day21", part) => {
+            let (count, plots) = match part {
+                "part1" => day_21_lib::render::reachable_plots(text),
+                _ => day_21_lib::render::odd_garden_plots(text),
+            };
+            (
+                count.to_string(),
+                vec![
+                    Layer::new("Rocks", WALL, tiles_of(text, &['#'])),
+                    Layer::new("Reachable plots", FILL, plots),
+                ],
+            )
+        }
+        ("day23", part) => {
+            let (steps, hike) = match part {
+                "part1" => day_23_lib::render::longest_hike(text),
+                _ => day_23_lib::render::longest_dry_hike(text),
+            };
+            (
+                steps.to_string(),
+                vec![
+                    Layer::new("Forest", WALL, tiles_of(text, &['#'])),
+                    Layer::new("Longest hike", PATH, hike),
+                ],
+            )
+        }
+        _ => return None,
+    };
+
+    let rows = text.lines().count();
+    let columns = text.lines().map(|line| line.len()).max().unwrap_or(0);
+
+    Some((
+        result,
+        Render {
+            rows,
+            columns,
+            layers,
+        },
+    ))
+}
+
+fn tiles_of(input: &str, symbols: &[char]) -> Vec<(usize, usize)> {
+    let mut tiles = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        for (j, c) in line.chars().enumerate() {
+            if symbols.contains(&c) {
+                tiles.push((i, j));
+            }
+        }
+    }
+    tiles
+}
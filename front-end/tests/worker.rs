@@ -0,0 +1,162 @@
+#![cfg(target_arch = "wasm32")]
+
+use front_end::worker::{debugger::Execution, Input, ProcessInput};
+use wasm_bindgen_test::*;
+use yew_agent::oneshot::Oneshot;
+
+// Runs the worker entry point the same way the browser does, without spawning a worker
+async fn process(day: &str, part: &str, input: &str) -> String {
+    let input = Input {
+        input: input.to_string(),
+        part: part.to_string(),
+        day: day.to_string(),
+    };
+    ProcessInput::create(input).await.result
+}
+
+macro_rules! example {
+    ($name:ident, $day:literal, $part:literal, $file:literal, $expected:literal) => {
+        #[wasm_bindgen_test]
+        async fn $name() {
+            let input = include_str!(concat!("./inputs/", $file));
+            assert_eq!(process($day, $part, input).await, $expected);
+        }
+    };
+}
+
+example!(day_1_part_1, "day1", "part1", "day1-part1.txt", "142");
+example!(day_1_part_2, "day1", "part2", "day1-part2.txt", "281");
+example!(day_2_part_1, "day2", "part1", "day2.txt", "8");
+example!(day_2_part_2, "day2", "part2", "day2.txt", "2286");
+example!(day_3_part_1, "day3", "part1", "day3.txt", "4361");
+example!(day_3_part_2, "day3", "part2", "day3.txt", "467835");
+example!(day_4_part_1, "day4", "part1", "day4.txt", "13");
+example!(day_4_part_2, "day4", "part2", "day4.txt", "30");
+example!(day_5_part_1, "day5", "part1", "day5.txt", "35");
+example!(day_5_part_2, "day5", "part2", "day5.txt", "46");
+example!(day_6_part_1, "day6", "part1", "day6.txt", "288");
+example!(day_6_part_2, "day6", "part2", "day6.txt", "71503");
+example!(day_7_part_1, "day7", "part1", "day7.txt", "6440");
+example!(day_7_part_2, "day7", "part2", "day7.txt", "5905");
+example!(day_8_part_1, "day8", "part1", "day8-part1.txt", "6");
+example!(day_8_part_2, "day8", "part2", "day8-part2.txt", "6");
+example!(day_9_part_1, "day9", "part1", "day9.txt", "114");
+example!(day_9_part_2, "day9", "part2", "day9.txt", "2");
+example!(day_10_part_1, "day10", "part1", "day10-part1.txt", "8");
+example!(day_10_part_2, "day10", "part2", "day10-part2.txt", "10");
+example!(day_11_part_1, "day11", "part1", "day11.txt", "374");
+example!(day_11_part_2, "day11", "part2", "day11.txt", "82000210");
+example!(day_12_part_1, "day12", "part1", "day12.txt", "21");
+example!(day_12_part_2, "day12", "part2", "day12.txt", "525152");
+example!(day_13_part_1, "day13", "part1", "day13.txt", "405");
+example!(day_13_part_2, "day13", "part2", "day13.txt", "400");
+example!(day_14_part_1, "day14", "part1", "day14.txt", "136");
+example!(day_14_part_2, "day14", "part2", "day14.txt", "64");
+example!(day_15_part_1, "day15", "part1", "day15.txt", "1320");
+example!(day_15_part_2, "day15", "part2", "day15.txt", "145");
+example!(day_16_part_1, "day16", "part1", "day16.txt", "46");
+example!(day_16_part_2, "day16", "part2", "day16.txt", "51");
+example!(day_17_part_1, "day17", "part1", "day17.txt", "102");
+example!(day_17_part_2, "day17", "part2", "day17.txt", "94");
+example!(day_18_part_1, "day18", "part1", "day18.txt", "62");
+example!(day_18_part_2, "day18", "part2", "day18.txt", "952408144115");
+example!(day_19_part_1, "day19", "part1", "day19.txt", "19114");
+example!(
+    day_19_part_2,
+    "day19",
+    "part2",
+    "day19.txt",
+    "167409079868000"
+);
+example!(day_20_part_1, "day20", "part1", "day20.txt", "11687500");
+example!(day_21_part_1, "day21", "part1", "day21.txt", "42");
+example!(day_22_part_1, "day22", "part1", "day22.txt", "5");
+example!(day_22_part_2, "day22", "part2", "day22.txt", "7");
+example!(day_23_part_1, "day23", "part1", "day23.txt", "94");
+example!(day_23_part_2, "day23", "part2", "day23.txt", "154");
+// the example moved into the test area of the real input, (p - 7) * 10^13 + 2 * 10^14
+// for every position, so the same two paths cross inside it
+example!(day_24_part_1, "day24", "part1", "day24.txt", "2");
+example!(day_25_part_1, "day25", "part1", "day25.txt", "54");
+
+// Day 20 and day 21 part 2 only work on inputs shaped like the real ones, so they get one
+// with a known answer
+
+#[wasm_bindgen_test]
+async fn day_20_part_2() {
+    let periods = [3733, 3797, 3877, 4051];
+    let input = day_20_lib::machine::generator::counters(&periods);
+    let expected: usize = periods.iter().map(|period| *period as usize).product();
+    assert_eq!(
+        process("day20", "part2", &input).await,
+        expected.to_string()
+    );
+}
+
+#[wasm_bindgen_test]
+async fn day_21_part_2() {
+    // without rocks the plots are the squares of the right parity, (steps + 1)^2 of them
+    let rows: Vec<String> = (0..131)
+        .map(|i| {
+            (0..131)
+                .map(|j| match (i, j) {
+                    (65, 65) => 'S',
+                    _ => '.',
+                })
+                .collect()
+        })
+        .collect();
+    let expected = (26501365_u64 + 1).pow(2);
+    assert_eq!(
+        process("day21", "part2", &rows.join("\n")).await,
+        expected.to_string()
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_missing_parts_are_not_implemented() {
+    let input = include_str!("./inputs/day24.txt");
+    assert_eq!(process("day24", "part2", input).await, "Not implemented");
+    let input = include_str!("./inputs/day25.txt");
+    assert_eq!(process("day25", "part2", input).await, "Not implemented");
+    assert_eq!(process("day26", "part1", "").await, "Not implemented");
+}
+
+#[wasm_bindgen_test]
+async fn test_output_has_elapsed_time_and_render() {
+    let input = Input {
+        input: include_str!("./inputs/day17.txt").to_string(),
+        part: "part1".to_string(),
+        day: "day17".to_string(),
+    };
+    let output = ProcessInput::create(input).await;
+    assert!(output.elapsed >= 0.0);
+    assert!(output.render.is_some());
+}
+
+#[wasm_bindgen_test]
+async fn test_day_19_returns_the_execution_trace() {
+    let mut input = Input {
+        input: include_str!("./inputs/day19.txt").to_string(),
+        part: "part1".to_string(),
+        day: "day19".to_string(),
+    };
+    let trace = ProcessInput::create(input.clone()).await.trace.unwrap();
+    assert_eq!(trace.variables, vec!["x", "m", "a", "s"]);
+    assert_eq!(trace.workflows.len(), 11);
+    match trace.execution {
+        Execution::Pieces(pieces) => {
+            assert_eq!(pieces.len(), 5);
+            assert_eq!(pieces[0].steps[0].workflow, "in");
+            assert!(pieces[0].accepted);
+        }
+        Execution::Ranges(_) => panic!("part 1 should trace the parts"),
+    }
+
+    input.part = "part2".to_string();
+    let trace = ProcessInput::create(input).await.trace.unwrap();
+    match trace.execution {
+        Execution::Ranges(steps) => assert_eq!(steps[0].ranges, vec![(1, 4000); 4]),
+        Execution::Pieces(_) => panic!("part 2 should trace the ranges"),
+    }
+}